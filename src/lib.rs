@@ -1,13 +1,19 @@
 #![no_std]
+// Conversion matrices are deliberately specified with seven decimals.
+#![allow(clippy::excessive_precision)]
+// Casts between f32 and the internal `Real` type are only no-ops without the f64 feature.
+#![cfg_attr(not(feature = "f64"), allow(clippy::unnecessary_cast))]
 
-/// Color manipulation library.
-///
-/// Allows conversion between RGB, XYZ and CIELUV color spaces,
-/// as well as creation of gradients through the CIELUV color space.
+//! Color manipulation library.
+//!
+//! Allows conversion between RGB, XYZ and CIELUV color spaces,
+//! as well as creation of gradients through the CIELUV color space.
 
 #[cfg(any(test, feature = "std"))]
 extern crate std;
 
+pub mod reference;
+
 #[cfg(test)]
 mod test;
 
@@ -16,6 +22,36 @@ use num_traits::Float;
 
 use core::fmt::Display;
 use core::fmt::Formatter;
+use core::time::Duration;
+
+/// Floating point type used internally by the color space conversions.
+///
+/// With the `f64` feature, conversion math runs in double precision,
+/// and results are rounded to `f32` when stored in the color types.
+#[cfg(feature = "f64")]
+type Real = f64;
+#[cfg(not(feature = "f64"))]
+type Real = f32;
+
+/// A color space that can be converted to any other color space with [`ColorSpace::convert`].
+///
+/// Conversions are composed through the XYZ color space, which every color space connects to.
+/// This trait is implemented automatically for every type that converts both to and from [`XYZ`],
+/// so adding a color space only requires those two conversions.
+pub trait ColorSpace: Copy + From<XYZ> + Into<XYZ> {
+    /// Convert this color into the color space `T`, e.g. `RGB::RED.convert::<HCL>()`.
+    fn convert<T: ColorSpace>(self) -> T {
+        T::from(self.into())
+    }
+
+    /// CIE 1931 (x, y) chromaticity coordinates of this color, see [`xyY`].
+    fn chromaticity(self) -> (f32, f32) {
+        let xyy = xyY::from(self.into());
+        (xyy.x, xyy.y)
+    }
+}
+
+impl<T: Copy + From<XYZ> + Into<XYZ>> ColorSpace for T {}
 
 /// Represents a color in the sRGB color space.
 ///
@@ -25,6 +61,7 @@ use core::fmt::Formatter;
 /// * `g` is the amount of green,
 /// * `b` is the amount of blue.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RGB {
     pub r: f32,
     pub g: f32,
@@ -57,6 +94,74 @@ impl RGB {
         g: 1.0,
         b: 1.0,
     };
+    /// Sepia, #704214.
+    pub const SEPIA: RGB = RGB {
+        r: 0.4392157,
+        g: 0.2588235,
+        b: 0.0784314,
+    };
+
+    /// Tint this color toward `color` by `amount`, from 0.0 (unchanged) to 1.0 (fully `color`).
+    ///
+    /// The colors are mixed in linear light rather than in the gamma-encoded sRGB space.
+    pub fn tint(&self, color: &RGB, amount: f32) -> Self {
+        let mix = |a: f32, b: f32| {
+            let a = srgb_to_linear(a as Real);
+            let b = srgb_to_linear(b as Real);
+            linear_to_srgb(a + amount as Real * (b - a)) as f32
+        };
+        Self {
+            r: mix(self.r, color.r).clamp(0.0, 1.0),
+            g: mix(self.g, color.g).clamp(0.0, 1.0),
+            b: mix(self.b, color.b).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Sepia toning by `amount`, from 0.0 (unchanged) to 1.0 (fully toned).
+    ///
+    /// The fully toned color keeps the luminance of this color,
+    /// with the chromaticity of [`RGB::SEPIA`].
+    pub fn sepia(&self, amount: f32) -> Self {
+        let y = XYZ::from(*self).y;
+        let tone = XYZ::from(Self::SEPIA);
+        let toned = XYZ {
+            x: tone.x * y / tone.y,
+            y,
+            z: tone.z * y / tone.y,
+        };
+        self.tint(&toned.into(), amount)
+    }
+
+    /// Interpolate between two colors by naive linear interpolation of hue, saturation
+    /// and value, as done by many legacy LED effects.
+    ///
+    /// The hue is interpolated directly between the two hues without wrapping around,
+    /// so this reproduces the intermediate colors of such effects during a migration.
+    /// Prefer [`CIELUV::interpolate`] for perceptually even gradients.
+    pub fn interpolate_hsv(&self, end: &Self, t: f32) -> Self {
+        HSV::from(*self).interpolate(&HSV::from(*end), t).into()
+    }
+
+    /// Convert from XYZ, with explicit treatment of negative linear values.
+    ///
+    /// Values above 1.0 are clamped regardless of `negative`.
+    pub fn from_xyz(xyz: &XYZ, negative: NegativeValues) -> Self {
+        let (r, g, b) = xyz.linear_rgb();
+        let convert = |c: Real| {
+            let c = match negative {
+                NegativeValues::Clamp => c.max(0.0),
+                NegativeValues::Flush { epsilon } if c.abs() < epsilon as Real => 0.0,
+                NegativeValues::Flush { .. } | NegativeValues::Propagate => c,
+            };
+            (linear_to_srgb(c) as f32).min(1.0)
+        };
+
+        Self {
+            r: convert(r),
+            g: convert(g),
+            b: convert(b),
+        }
+    }
 }
 
 impl Display for RGB {
@@ -68,18 +173,30 @@ impl Display for RGB {
     }
 }
 
+/// How conversions to RGB treat negative linear values, see [`RGB::from_xyz`].
+///
+/// Matrix round-off produces tiny negative values for colors on the edge of the gamut,
+/// and the choice affects how smooth gradients are close to black.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum NegativeValues {
+    /// Clamp negative values to zero. This is what the `From` conversions do.
+    #[default]
+    Clamp,
+    /// Flush values closer to zero than `epsilon` to zero, both negative and positive,
+    /// and pass other values through.
+    Flush { epsilon: f32 },
+    /// Pass negative values through unchanged, for further processing outside of the gamut.
+    Propagate,
+}
+
 impl From<XYZ> for RGB {
     fn from(xyz: XYZ) -> Self {
-        // sYCC: Amendment 1 to IEC 61966-2-1:1999.
-        // Higher conversion precision with seven decimals.
-        let r = 3.2406255 * xyz.x - 1.5372080 * xyz.y - 0.4986286 * xyz.z;
-        let g = -0.9689307 * xyz.x + 1.8758561 * xyz.y + 0.0415175 * xyz.z;
-        let b = 0.0557101 * xyz.x - 0.2040211 * xyz.y + 1.0570959 * xyz.z;
+        let (r, g, b) = xyz.linear_rgb();
 
         Self {
-            r: linear_to_srgb(r).clamp(0.0, 1.0),
-            g: linear_to_srgb(g).clamp(0.0, 1.0),
-            b: linear_to_srgb(b).clamp(0.0, 1.0),
+            r: (linear_to_srgb(r) as f32).clamp(0.0, 1.0),
+            g: (linear_to_srgb(g) as f32).clamp(0.0, 1.0),
+            b: (linear_to_srgb(b) as f32).clamp(0.0, 1.0),
         }
     }
 }
@@ -98,348 +215,4211 @@ impl From<HCL> for RGB {
     }
 }
 
-/// Represents a color using RGB and a white component.
+/// Represents a color in the Display P3 working space, used by wide-gamut displays.
 ///
+/// Display P3 has wider primaries than sRGB, but shares the sRGB transfer function and white point.
 /// Values in the range of 0.0..1.0.
 ///
 /// * `r` is the amount of red,
 /// * `g` is the amount of green,
-/// * `b` is the amount of blue,
-/// * `w` is the amount of white.
+/// * `b` is the amount of blue.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub struct RGBW {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct P3 {
     pub r: f32,
     pub g: f32,
     pub b: f32,
-    pub w: f32,
 }
 
-impl Display for RGBW {
+impl Display for P3 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let r = self.r;
         let g = self.g;
         let b = self.b;
-        let w = self.w;
-        write!(f, "RGBW R={r:1.2}, G={g:1.2}, B={b:1.2}, W={w:1.2}")
+        write!(f, "P3 R={r:1.2}, G={g:1.2}, B={b:1.2}")
     }
 }
 
-/// For pure RGB values, we convert them directly into RGBW without adding any white.
-impl From<RGB> for RGBW {
-    fn from(rgb: RGB) -> Self {
-        Self {
-            r: rgb.r,
-            g: rgb.g,
-            b: rgb.b,
-            w: 0.0,
-        }
+/// Colors outside of the P3 gamut are clamped.
+impl From<XYZ> for P3 {
+    fn from(xyz: XYZ) -> Self {
+        let [r, g, b] = xyz
+            .linear(&XYZ_TO_P3)
+            .map(|c| (linear_to_srgb(c) as f32).clamp(0.0, 1.0));
+        Self { r, g, b }
     }
 }
 
-/// Direct conversion from XYZ to RGBW.
-///
-/// The white component is ignored when converting directly from XYZ.
-/// You may use the CIELUV to RGBW conversion instead.
-impl From<XYZ> for RGBW {
-    fn from(xyz: XYZ) -> Self {
-        RGB::from(xyz).into()
+impl From<P3> for XYZ {
+    fn from(p3: P3) -> Self {
+        let linear = [p3.r, p3.g, p3.b].map(|c| srgb_to_linear(c as Real));
+        XYZ::from_linear(&P3_TO_XYZ, linear)
     }
 }
 
-/// Conversion from CIELUV to RGBW is a bit more tricky than with plain RGB,
-/// because we have to derive a value for the white component.
-///
-/// Here, this is done by first performing the CIELUV->XYZ->RGB conversion,
-/// and subsequently using the saturation value from CIELUV to determine
-/// how much of the RGB and white components to use.
+/// Conversions between P3 and RGB is done through the XYZ color space.
+/// Colors outside of the sRGB gamut are clamped.
+impl From<P3> for RGB {
+    fn from(p3: P3) -> Self {
+        XYZ::from(p3).into()
+    }
+}
+
+impl From<RGB> for P3 {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
+    }
+}
+
+/// Represents a color in the Adobe RGB (1998) working space, common for photography.
 ///
-/// The white amount is defined as Y * (1.0 - saturation), whereas the color
-/// RGB components are multiplied by the saturation value.
+/// Adobe RGB has a wider green primary than sRGB, and uses a gamma 2.2 transfer function.
+/// Values in the range of 0.0..1.0.
 ///
-/// This calculation produces nice results on SK6812 LEDs with good saturation
-/// for deep reds while avoiding whites that are a mixture of the RGB components.
-impl From<CIELUV> for RGBW {
-    fn from(cieluv: CIELUV) -> Self {
-        // Color saturation from 0..1
-        let saturation = cieluv.saturation();
-        let whiteness = 1.0 - saturation;
-
-        let xyz = XYZ::from(cieluv);
+/// * `r` is the amount of red,
+/// * `g` is the amount of green,
+/// * `b` is the amount of blue.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdobeRGB {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
 
-        // sYCC: Amendment 1 to IEC 61966-2-1:1999.
-        // Higher conversion precision with seven decimals.
-        let r = 3.2406255 * xyz.x - 1.5372080 * xyz.y - 0.4986286 * xyz.z;
-        let g = -0.9689307 * xyz.x + 1.8758561 * xyz.y + 0.0415175 * xyz.z;
-        let b = 0.0557101 * xyz.x - 0.2040211 * xyz.y + 1.0570959 * xyz.z;
+impl Display for AdobeRGB {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let r = self.r;
+        let g = self.g;
+        let b = self.b;
+        write!(f, "Adobe RGB R={r:1.2}, G={g:1.2}, B={b:1.2}")
+    }
+}
 
-        //let rgb_max = r.max(g).max(b);
+/// Colors outside of the Adobe RGB gamut are clamped.
+impl From<XYZ> for AdobeRGB {
+    fn from(xyz: XYZ) -> Self {
+        let [r, g, b] = xyz
+            .linear(&XYZ_TO_ADOBE_RGB)
+            .map(|c| (linear_to_adobe(c) as f32).min(1.0));
+        Self { r, g, b }
+    }
+}
 
-        let r = r * saturation;
-        let g = g * saturation;
-        let b = b * saturation;
-        let w = xyz.y * whiteness;
+impl From<AdobeRGB> for XYZ {
+    fn from(adobe: AdobeRGB) -> Self {
+        let linear = [adobe.r, adobe.g, adobe.b].map(|c| adobe_to_linear(c as Real));
+        XYZ::from_linear(&ADOBE_RGB_TO_XYZ, linear)
+    }
+}
 
-        Self {
-            r: linear_to_srgb(r).clamp(0.0, 1.0),
-            g: linear_to_srgb(g).clamp(0.0, 1.0),
-            b: linear_to_srgb(b).clamp(0.0, 1.0),
-            w: linear_to_srgb(w).clamp(0.0, 1.0),
-        }
+/// Conversions between Adobe RGB and RGB is done through the XYZ color space.
+/// Colors outside of the sRGB gamut are clamped.
+impl From<AdobeRGB> for RGB {
+    fn from(adobe: AdobeRGB) -> Self {
+        XYZ::from(adobe).into()
     }
 }
 
-/// Conversions from HCL to RGBW is done through CIELUV, then the XYZ color space.
-impl From<HCL> for RGBW {
-    fn from(hcl: HCL) -> Self {
-        CIELUV::from(hcl).into()
+impl From<RGB> for AdobeRGB {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
     }
 }
 
-/// CIE 1931 XYZ color space, derived from CIE RGB in an effort to simplify the math.
-/// This color space defines the relationship between the visible spectrum
-/// and the visual sensation of specific colors by human color vision.
+/// Represents a color in linear light, using the sRGB primaries without the sRGB transfer function.
 ///
-/// Values in the range of 0.0..1.0.
+/// Values nominally in the range of 0.0..1.0, proportional to the light emitted.
+/// Blending and brightness math is physically correct in this space.
+/// Values outside of the range are kept until conversion to [`RGB`].
 ///
-/// * `x` is a mix of all three RGB curves chosen to be nonnegative,
-/// * `y` is the luminance, and
-/// * `z` is quasi-equal to blue (from CIE RGB).
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct XYZ {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+/// * `r` is the amount of red,
+/// * `g` is the amount of green,
+/// * `b` is the amount of blue.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearRGB {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
 }
 
-// Constants for D65 white point
-const X_REF: f32 = 95.047;
-const Y_REF: f32 = 100.0;
-const Z_REF: f32 = 108.883;
-
-// XYZ/LUV conversion
-const K: f32 = 24389.0 / 27.0;
-const E: f32 = 216.0 / 24389.0;
-const U_PRIME_REF: f32 = 4.0 * X_REF / (X_REF + 15.0 * Y_REF + 3.0 * Z_REF);
-const V_PRIME_REF: f32 = 9.0 * Y_REF / (X_REF + 15.0 * Y_REF + 3.0 * Z_REF);
-
-impl XYZ {
-    #[inline]
-    fn u_prime(&self) -> f32 {
-        4.0 * self.x / (self.x + 15.0 * self.y + 3.0 * self.z)
+impl LinearRGB {
+    /// Multiply the light of every channel by `factor`.
+    pub fn scale(&self, factor: f32) -> Self {
+        Self {
+            r: self.r * factor,
+            g: self.g * factor,
+            b: self.b * factor,
+        }
     }
 
-    #[inline]
-    fn v_prime(&self) -> f32 {
-        9.0 * self.y / (self.x + 15.0 * self.y + 3.0 * self.z)
+    /// Mix with `other` by `amount`, from 0.0 (this color) to 1.0 (`other`).
+    pub fn mix(&self, other: &Self, amount: f32) -> Self {
+        Self {
+            r: lerp(self.r, other.r, amount),
+            g: lerp(self.g, other.g, amount),
+            b: lerp(self.b, other.b, amount),
+        }
     }
 
-    #[inline]
-    fn y_ref(&self) -> f32 {
-        self.y / Y_REF
+    /// Relative luminance, the Y component of [`XYZ`].
+    pub fn luminance(&self) -> f32 {
+        XYZ::from(*self).y
     }
 }
 
-impl Display for XYZ {
-    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let x = self.x;
-        let y = self.y;
-        let z = self.z;
-        write!(f, "CIEXYZ X={x:1.2}, Y={y:1.2}, Z={z:1.2}")
+impl From<RGB> for LinearRGB {
+    fn from(rgb: RGB) -> Self {
+        Self {
+            r: srgb_to_linear(rgb.r as Real) as f32,
+            g: srgb_to_linear(rgb.g as Real) as f32,
+            b: srgb_to_linear(rgb.b as Real) as f32,
+        }
     }
 }
 
-impl From<RGB> for XYZ {
-    fn from(rgb: RGB) -> Self {
-        let r = srgb_to_linear(rgb.r);
-        let g = srgb_to_linear(rgb.g);
-        let b = srgb_to_linear(rgb.b);
-
-        // Based on sRGB Working Space Matrix
-        // http://www.brucelindbloom.com/Eqn_RGB_XYZ_Matrix.html
+/// Values outside of 0.0..1.0 are clamped.
+impl From<LinearRGB> for RGB {
+    fn from(rgb: LinearRGB) -> Self {
         Self {
-            x: r * 0.4124564 + g * 0.3575761 + b * 0.1804375,
-            y: r * 0.2126729 + g * 0.7151522 + b * 0.0721750,
-            z: r * 0.0193339 + g * 0.1191920 + b * 0.9503041,
+            r: (linear_to_srgb(rgb.r as Real) as f32).clamp(0.0, 1.0),
+            g: (linear_to_srgb(rgb.g as Real) as f32).clamp(0.0, 1.0),
+            b: (linear_to_srgb(rgb.b as Real) as f32).clamp(0.0, 1.0),
         }
     }
 }
 
-impl From<CIELUV> for XYZ {
-    fn from(cieluv: CIELUV) -> Self {
-        if cieluv.l == 0.0 {
-            return XYZ {
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
-            };
+impl From<XYZ> for LinearRGB {
+    fn from(xyz: XYZ) -> Self {
+        let (r, g, b) = xyz.linear_rgb();
+        Self {
+            r: r as f32,
+            g: g as f32,
+            b: b as f32,
         }
+    }
+}
 
-        let u_prime = cieluv.u / (13.0 * cieluv.l) + 0.19783000664283;
-        let v_prime = cieluv.v / (13.0 * cieluv.l) + 0.46831999493879;
-
-        let y = if cieluv.l > 8.0 {
-            Y_REF * ((cieluv.l + 16.0) / 116.0).powi(3)
-        } else {
-            Y_REF * cieluv.l / 903.3
-        };
-
-        let x = y * 9.0 * u_prime / (4.0 * v_prime);
-        let z = y * (12.0 - 3.0 * u_prime - 20.0 * v_prime) / (4.0 * v_prime);
-
-        XYZ { x, y, z }
+/// No white is added, as with the conversion from RGB.
+impl From<LinearRGB> for RGBW {
+    fn from(rgb: LinearRGB) -> Self {
+        RGB::from(rgb).into()
     }
 }
 
-/// Represents a color using the CIE 1976 L*, u*, v* color space.
+/// Represents a color using the HSV (hue, saturation, value) model of the sRGB color space,
+/// also known as HSB, as used by most LED effect libraries.
 ///
-/// * `l` is the luminance, with values nominally within `0.0..9.0`,
-/// * `u` is the horizontal axis (green/red), with values approximately `-1.34..2.24`, and
-/// * `v` is the vertical axis (blue/yellow), with values approximately `-1.40..1.22`.
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
+/// * `s` is the saturation, ranging from `0.0..1.0`, and
+/// * `v` is the value, ranging from `0.0..1.0`.
 ///
+/// See [`FastLedHsv`] for FastLED's 8-bit variant with its own hue distribution.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub struct CIELUV {
-    pub l: f32,
-    pub u: f32,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HSV {
+    pub h: f32,
+    pub s: f32,
     pub v: f32,
 }
 
-impl CIELUV {
-    /// Interpolate between two colors based on a parameter `t` (0.0 to 1.0).
-    /// `t = 0.0` returns the start color, `t = 1.0` returns the end color.
-    /// Any value in between is derived using linear interpolation in the
-    /// CIELUV color space.
+impl HSV {
+    /// Interpolate between two colors by naive linear interpolation of each component,
+    /// see [`RGB::interpolate_hsv`].
     pub fn interpolate(&self, end: &Self, t: f32) -> Self {
         Self {
-            l: lerp(self.l, end.l, t),
-            u: lerp(self.u, end.u, t),
+            h: lerp(self.h, end.h, t),
+            s: lerp(self.s, end.s, t),
             v: lerp(self.v, end.v, t),
         }
     }
+}
 
-    /// Hue is expressed in degrees between 0.0..360.0.
-    pub fn hue(&self) -> f32 {
-        let mut h = self.v.atan2(self.u).to_degrees();
-        if h < 0.0 {
-            h += 360.0;
-        }
-        h
-    }
+impl From<RGB> for HSV {
+    fn from(rgb: RGB) -> Self {
+        let (r, g, b) = (rgb.r as Real, rgb.g as Real, rgb.b as Real);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
 
-    pub fn chroma(&self) -> f32 {
-        (self.u.powi(2) + self.v.powi(2)).sqrt()
-    }
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
 
-    pub fn saturation(&self) -> f32 {
-        if self.l <= 0.0 {
-            return 0.0;
+        Self {
+            h: (if h < 0.0 { h + 360.0 } else { h }) as f32,
+            s: (if max == 0.0 { 0.0 } else { delta / max }) as f32,
+            v: max as f32,
         }
-        self.chroma() / self.l
     }
 }
 
-impl Display for CIELUV {
-    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let l = self.l;
-        let u = self.u;
-        let v = self.v;
-        write!(f, "CIELUV L*={l:1.2}, u*={u:1.2}, v*={v:1.2}")
+impl From<HSV> for RGB {
+    fn from(hsv: HSV) -> Self {
+        let h = hsv.h as Real % 360.0;
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        let (s, v) = (hsv.s as Real, hsv.v as Real);
+        let channel = |n: Real| {
+            let k = (n + h / 60.0) % 6.0;
+            (v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)) as f32
+        };
+        RGB {
+            r: channel(5.0),
+            g: channel(3.0),
+            b: channel(1.0),
+        }
     }
 }
 
-impl From<XYZ> for CIELUV {
-    // Verified here: http://www.brucelindbloom.com/index.html?Eqn_XYZ_to_Luv.html
-    // Introduced constants due to http://www.brucelindbloom.com/LContinuity.html
+/// Conversions between HSV and other color spaces are done through the RGB color space.
+impl From<XYZ> for HSV {
     fn from(xyz: XYZ) -> Self {
-        if xyz.x == 0.0 && xyz.y == 0.0 && xyz.z == 0.0 {
-            return Self {
-                l: 0.0,
-                u: 0.0,
-                v: 0.0,
-            };
-        }
-
-        let u_prime = xyz.u_prime();
-        let v_prime = xyz.v_prime();
-        let y_ref = xyz.y_ref();
-
-        let l = if y_ref > E {
-            116.0 * y_ref.powf(1.0 / 3.0) - 16.0
-        } else {
-            K * y_ref
-        };
+        RGB::from(xyz).into()
+    }
+}
 
-        Self {
-            l,
-            u: 13.0 * l * (u_prime - U_PRIME_REF),
-            v: 13.0 * l * (v_prime - V_PRIME_REF),
-        }
+impl From<HSV> for XYZ {
+    fn from(hsv: HSV) -> Self {
+        RGB::from(hsv).into()
     }
 }
 
-/// Conversions to CIELUV from RGB is done through the XYZ color space.
-impl From<RGB> for CIELUV {
-    fn from(rgb: RGB) -> Self {
-        XYZ::from(rgb).into()
+impl From<CIELUV> for HSV {
+    fn from(cieluv: CIELUV) -> Self {
+        RGB::from(cieluv).into()
     }
 }
 
-/// Conversions to HCL from RGB is done by first converting to CIELUV, then converting to HCL.
-impl From<HCL> for CIELUV {
-    fn from(hcl: HCL) -> Self {
-        let h_rad = hcl.h.to_radians(); // Convert hue to radians
-        let u = hcl.c * h_rad.cos();
-        let v = hcl.c * h_rad.sin();
-        CIELUV { l: hcl.l, u, v }
+impl From<HSV> for CIELUV {
+    fn from(hsv: HSV) -> Self {
+        RGB::from(hsv).into()
     }
 }
 
-/// CIELCh, also known as HCL or CIELUVch, is a cylindrical representation of the CIELUV color space.
+/// Represents a color using the HWB (hue, whiteness, blackness) model of the sRGB color space.
 ///
 /// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
-/// * `c` is the chromaticity, ranging from `0.0..1.0`, and
-/// * `l` is the luminance, ranging from `0.0..1.0`.
+/// * `w` is the amount of white mixed in, ranging from `0.0..1.0`, and
+/// * `b` is the amount of black mixed in, ranging from `0.0..1.0`.
 ///
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct HCL {
+/// Colors where `w + b` is 1.0 or more are grays.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HWB {
     pub h: f32,
-    pub c: f32,
+    pub w: f32,
+    pub b: f32,
+}
+
+impl From<HSV> for HWB {
+    fn from(hsv: HSV) -> Self {
+        let (s, v) = (hsv.s as Real, hsv.v as Real);
+        Self {
+            h: hsv.h,
+            w: ((1.0 - s) * v) as f32,
+            b: (1.0 - v) as f32,
+        }
+    }
+}
+
+impl From<HWB> for HSV {
+    fn from(hwb: HWB) -> Self {
+        let (w, b) = (hwb.w as Real, hwb.b as Real);
+        let sum = w + b;
+        if sum >= 1.0 {
+            return Self {
+                h: hwb.h,
+                s: 0.0,
+                v: (w / sum) as f32,
+            };
+        }
+        let v = 1.0 - b;
+        Self {
+            h: hwb.h,
+            s: (1.0 - w / v) as f32,
+            v: v as f32,
+        }
+    }
+}
+
+impl From<RGB> for HWB {
+    fn from(rgb: RGB) -> Self {
+        HSV::from(rgb).into()
+    }
+}
+
+impl From<HWB> for RGB {
+    fn from(hwb: HWB) -> Self {
+        HSV::from(hwb).into()
+    }
+}
+
+/// Conversions between HWB and other color spaces are done through the RGB color space.
+impl From<XYZ> for HWB {
+    fn from(xyz: XYZ) -> Self {
+        RGB::from(xyz).into()
+    }
+}
+
+impl From<HWB> for XYZ {
+    fn from(hwb: HWB) -> Self {
+        RGB::from(hwb).into()
+    }
+}
+
+impl From<HWB> for CIELUV {
+    fn from(hwb: HWB) -> Self {
+        RGB::from(hwb).into()
+    }
+}
+
+/// Represents a color using the HSL (hue, saturation, lightness) model of the sRGB color space,
+/// as used by CSS and most web color pickers.
+///
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
+/// * `s` is the saturation, ranging from `0.0..1.0`, and
+/// * `l` is the lightness, ranging from `0.0..1.0`, where 0.5 is the most saturated.
+///
+/// Unlike [`HCL`], the lightness is not perceptual, so prefer converting to CIELUV
+/// for gradients and other manipulation.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HSL {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+impl From<RGB> for HSL {
+    fn from(rgb: RGB) -> Self {
+        let HSV { h, v, .. } = HSV::from(rgb);
+        let v = v as Real;
+        let min = (rgb.r as Real).min(rgb.g as Real).min(rgb.b as Real);
+        let l = (v + min) / 2.0;
+        let s = if l <= 0.0 || l >= 1.0 {
+            0.0
+        } else {
+            (v - l) / l.min(1.0 - l)
+        };
+        Self {
+            h,
+            s: s as f32,
+            l: l as f32,
+        }
+    }
+}
+
+impl From<HSL> for RGB {
+    fn from(hsl: HSL) -> Self {
+        let (s, l) = (hsl.s as Real, hsl.l as Real);
+        let v = l + s * l.min(1.0 - l);
+        let s = if v <= 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+        HSV {
+            h: hsl.h,
+            s: s as f32,
+            v: v as f32,
+        }
+        .into()
+    }
+}
+
+/// Conversions between HSL and other color spaces are done through the RGB color space.
+impl From<XYZ> for HSL {
+    fn from(xyz: XYZ) -> Self {
+        RGB::from(xyz).into()
+    }
+}
+
+impl From<HSL> for XYZ {
+    fn from(hsl: HSL) -> Self {
+        RGB::from(hsl).into()
+    }
+}
+
+impl From<HSL> for CIELUV {
+    fn from(hsl: HSL) -> Self {
+        RGB::from(hsl).into()
+    }
+}
+
+/// Represents a color using naive, device-independent CMYK (cyan, magenta, yellow, key).
+///
+/// Values in the range of 0.0..1.0.
+///
+/// The conversion to and from RGB does not account for ink or paper characteristics,
+/// so it is only suited for approximate previews.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CMYK {
+    pub c: f32,
+    pub m: f32,
+    pub y: f32,
+    pub k: f32,
+}
+
+impl From<RGB> for CMYK {
+    fn from(rgb: RGB) -> Self {
+        let (r, g, b) = (rgb.r as Real, rgb.g as Real, rgb.b as Real);
+        let max = r.max(g).max(b);
+        if max <= 0.0 {
+            return Self {
+                k: 1.0,
+                ..Self::default()
+            };
+        }
+        Self {
+            c: (1.0 - r / max) as f32,
+            m: (1.0 - g / max) as f32,
+            y: (1.0 - b / max) as f32,
+            k: (1.0 - max) as f32,
+        }
+    }
+}
+
+impl From<CMYK> for RGB {
+    fn from(cmyk: CMYK) -> Self {
+        let white = 1.0 - cmyk.k as Real;
+        Self {
+            r: ((1.0 - cmyk.c as Real) * white) as f32,
+            g: ((1.0 - cmyk.m as Real) * white) as f32,
+            b: ((1.0 - cmyk.y as Real) * white) as f32,
+        }
+    }
+}
+
+/// Conversions between CMYK and other color spaces are done through the RGB color space.
+impl From<XYZ> for CMYK {
+    fn from(xyz: XYZ) -> Self {
+        RGB::from(xyz).into()
+    }
+}
+
+impl From<CMYK> for XYZ {
+    fn from(cmyk: CMYK) -> Self {
+        RGB::from(cmyk).into()
+    }
+}
+
+/// Matrix standard of a [`YCbCr`] color.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YCbCrMatrix {
+    /// ITU-R BT.601, used by standard definition video and JPEG.
+    Bt601,
+    /// ITU-R BT.709, used by high definition video.
+    #[default]
+    Bt709,
+}
+
+impl YCbCrMatrix {
+    /// Luma coefficients of red and blue.
+    fn coefficients(self) -> (Real, Real) {
+        match self {
+            YCbCrMatrix::Bt601 => (0.299, 0.114),
+            YCbCrMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+/// Range of the code values of a [`YCbCr`] color.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YCbCrRange {
+    /// Code values use the full range, 0..255 at 8 bits.
+    #[default]
+    Full,
+    /// Code values use the limited "studio" range, 16..235 for luma and 16..240 for chroma at 8 bits.
+    Limited,
+}
+
+/// Represents a color in the YCbCr encoding of sRGB, as produced by cameras and video decoders.
+///
+/// Values are code values scaled to the range of 0.0..1.0, that is, the 8-bit code value divided by 255.
+///
+/// * `y` is the luma,
+/// * `cb` is the blue-difference chroma, and
+/// * `cr` is the red-difference chroma, both centered around 128 / 255.
+///
+/// The encoding depends on the matrix standard and the range, which must be given for every conversion.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct YCbCr {
+    pub y: f32,
+    pub cb: f32,
+    pub cr: f32,
+}
+
+impl YCbCr {
+    /// Encode an RGB color.
+    pub fn from_rgb(rgb: &RGB, matrix: YCbCrMatrix, range: YCbCrRange) -> Self {
+        let (kr, kb) = matrix.coefficients();
+        let (r, g, b) = (rgb.r as Real, rgb.g as Real, rgb.b as Real);
+        let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+        let pb = (b - y) / (2.0 * (1.0 - kb));
+        let pr = (r - y) / (2.0 * (1.0 - kr));
+
+        let (y, cb, cr) = match range {
+            YCbCrRange::Full => (
+                y,
+                // With an offset of 128, the largest chroma exceeds 255 by half a code value.
+                ((128.0 + 255.0 * pb) / 255.0).min(1.0),
+                ((128.0 + 255.0 * pr) / 255.0).min(1.0),
+            ),
+            YCbCrRange::Limited => (
+                (16.0 + 219.0 * y) / 255.0,
+                (128.0 + 224.0 * pb) / 255.0,
+                (128.0 + 224.0 * pr) / 255.0,
+            ),
+        };
+        Self {
+            y: y as f32,
+            cb: cb as f32,
+            cr: cr as f32,
+        }
+    }
+
+    /// Decode into an RGB color, clamping colors outside of the sRGB gamut.
+    pub fn to_rgb(&self, matrix: YCbCrMatrix, range: YCbCrRange) -> RGB {
+        let (kr, kb) = matrix.coefficients();
+        let (y, cb, cr) = (self.y as Real, self.cb as Real, self.cr as Real);
+        let (y, pb, pr) = match range {
+            YCbCrRange::Full => (y, cb - 128.0 / 255.0, cr - 128.0 / 255.0),
+            YCbCrRange::Limited => (
+                (y * 255.0 - 16.0) / 219.0,
+                (cb * 255.0 - 128.0) / 224.0,
+                (cr * 255.0 - 128.0) / 224.0,
+            ),
+        };
+
+        let r = y + 2.0 * (1.0 - kr) * pr;
+        let b = y + 2.0 * (1.0 - kb) * pb;
+        let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+
+        RGB {
+            r: (r as f32).clamp(0.0, 1.0),
+            g: (g as f32).clamp(0.0, 1.0),
+            b: (b as f32).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Represents a color in the sRGB color space with 8 bits per channel, as sent to most LEDs.
+///
+/// * `r` is the amount of red,
+/// * `g` is the amount of green,
+/// * `b` is the amount of blue.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RGB8 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Display for RGB8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let r = self.r;
+        let g = self.g;
+        let b = self.b;
+        write!(f, "RGB8 R={r}, G={g}, B={b}")
+    }
+}
+
+impl RGB8 {
+    /// Quantize a color for the LED at `index` in a strip, offsetting it by up to half a level
+    /// before rounding.
+    ///
+    /// The offsets follow the R1 low-discrepancy sequence, which spreads them evenly like blue noise,
+    /// so the rounding errors of adjacent LEDs cancel out and smooth gradients show less banding.
+    /// The offsets are fixed per LED, so unlike temporal dithering they do not flicker on camera.
+    /// Colors that are exactly representable in 8 bits are unchanged.
+    pub fn dithered(rgb: &RGB, index: usize) -> Self {
+        // Fractional part of index / golden ratio in 0.32 fixed point, centered around 0.0.
+        // Integer math keeps double precision float out of f32 builds.
+        let fraction = (index as u32).wrapping_mul(0x9E3779B9) >> 8;
+        let offset = fraction as f32 / (1 << 24) as f32 - 0.5;
+        let to_u8 = |c: f32| {
+            (c.clamp(0.0, 1.0) * 255.0 + offset)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Self {
+            r: to_u8(rgb.r),
+            g: to_u8(rgb.g),
+            b: to_u8(rgb.b),
+        }
+    }
+
+    /// Compensate for LEDs that do not light up below `level`, by remapping
+    /// every non-zero channel from 1..255 into `level`..255.
+    ///
+    /// Without compensation, the dark end of a fade to black is invisible
+    /// and the LED appears to switch off abruptly.
+    pub fn black_point(&self, level: u8) -> Self {
+        let remap = |c: u8| match c {
+            0 => 0,
+            _ => level.max(1) + ((c as u16 - 1) * (255 - level.max(1) as u16) / 254) as u8,
+        };
+        Self {
+            r: remap(self.r),
+            g: remap(self.g),
+            b: remap(self.b),
+        }
+    }
+}
+
+impl From<RGB> for RGB8 {
+    fn from(rgb: RGB) -> Self {
+        let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self {
+            r: to_u8(rgb.r),
+            g: to_u8(rgb.g),
+            b: to_u8(rgb.b),
+        }
+    }
+}
+
+impl From<RGB8> for RGB {
+    fn from(rgb: RGB8) -> Self {
+        Self {
+            r: rgb.r as f32 / 255.0,
+            g: rgb.g as f32 / 255.0,
+            b: rgb.b as f32 / 255.0,
+        }
+    }
+}
+
+/// Accuracy of converting an 8-bit color to CIELUV and back, see [`round_trip_error`].
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RoundTripError {
+    /// Perceptual difference between the input and output colors, see [`CIELUV::delta_e`].
+    pub delta_e: f32,
+    /// Absolute difference of each 8-bit channel, in the order red, green, blue.
+    pub channels: [u8; 3],
+}
+
+/// Convert an 8-bit color through RGB->CIELUV->RGB and back to 8 bits, and report the error introduced.
+///
+/// Use this to validate the conversion pipeline on a target, for instance over all colors of a palette.
+pub fn round_trip_error(rgb: RGB8) -> RoundTripError {
+    let input = CIELUV::from(RGB::from(rgb));
+    let output = RGB8::from(RGB::from(input));
+
+    RoundTripError {
+        delta_e: input.delta_e(&RGB::from(output).into()),
+        channels: [
+            rgb.r.abs_diff(output.r),
+            rgb.g.abs_diff(output.g),
+            rgb.b.abs_diff(output.b),
+        ],
+    }
+}
+
+/// Debug formatter that displays a color as a block in terminals supporting 24-bit ANSI colors,
+/// followed by its hex value, e.g. `#ff8000`.
+#[cfg(any(test, feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swatch(pub RGB);
+
+#[cfg(any(test, feature = "std"))]
+impl Display for Swatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let RGB8 { r, g, b } = self.0.into();
+        write!(f, "\x1b[48;2;{r};{g};{b}m   \x1b[0m #{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+/// Formatter that exports baked colors as a C array initializer, for pasting precomputed
+/// gradients and palettes into firmware that does not use this crate.
+///
+/// Each color is written as three bytes in RGB order, one color per line:
+///
+/// ```text
+/// const uint8_t palette[6] = {
+///     0xff, 0x80, 0x00,
+///     0x00, 0x00, 0xff
+/// };
+/// ```
+#[cfg(any(test, feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CArray<'a> {
+    name: &'a str,
+    colors: &'a [RGB8],
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<'a> CArray<'a> {
+    /// Export `colors` as a C variable called `name`.
+    ///
+    /// Returns `None` if `colors` is empty, as ISO C does not allow arrays of length zero.
+    pub fn new(name: &'a str, colors: &'a [RGB8]) -> Option<Self> {
+        if colors.is_empty() {
+            return None;
+        }
+        Some(Self { name, colors })
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl Display for CArray<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = self.name;
+        let len = self.colors.len() * 3;
+        write!(f, "const uint8_t {name}[{len}] = {{")?;
+        for (i, RGB8 { r, g, b }) in self.colors.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "\n    0x{r:02x}, 0x{g:02x}, 0x{b:02x}")?;
+        }
+        write!(f, "\n}};")
+    }
+}
+
+/// Represents a color using RGB and a white component.
+///
+/// Values in the range of 0.0..1.0.
+///
+/// * `r` is the amount of red,
+/// * `g` is the amount of green,
+/// * `b` is the amount of blue,
+/// * `w` is the amount of white.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RGBW {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub w: f32,
+}
+
+impl Display for RGBW {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let r = self.r;
+        let g = self.g;
+        let b = self.b;
+        let w = self.w;
+        write!(f, "RGBW R={r:1.2}, G={g:1.2}, B={b:1.2}, W={w:1.2}")
+    }
+}
+
+impl RGBW {
+    /// Conversion from CIELUV tuned for pastel colors, which the [`From<CIELUV>`] conversion
+    /// washes out to near white.
+    ///
+    /// The white component is derived the same way, but the chroma of the color mixed
+    /// into the RGB components is boosted by up to `1.0 + boost` for the least saturated colors,
+    /// which keeps pastel hues recognizable next to the white. Saturated colors are unaffected.
+    pub fn from_pastel(cieluv: &CIELUV, boost: f32) -> Self {
+        let saturation = cieluv.saturation() as Real;
+        let whiteness = (1.0 - saturation).max(0.0);
+
+        let boosted = cieluv.scale_chroma(1.0 + boost * whiteness as f32);
+        let (r, g, b) = XYZ::from(boosted).linear_rgb();
+        let w = XYZ::from(*cieluv).y as Real * whiteness;
+
+        Self {
+            r: (linear_to_srgb(r * saturation) as f32).clamp(0.0, 1.0),
+            g: (linear_to_srgb(g * saturation) as f32).clamp(0.0, 1.0),
+            b: (linear_to_srgb(b * saturation) as f32).clamp(0.0, 1.0),
+            w: (linear_to_srgb(w) as f32).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Compensate for LEDs that do not light up below `level` (0.0..1.0), by remapping
+    /// every channel that is lit at 8 bit resolution into `level`..1.0.
+    ///
+    /// Channels below half an 8 bit step stay off, see [`RGB8::black_point`].
+    pub fn black_point(&self, level: f32) -> Self {
+        let remap = |c: f32| {
+            if c < 0.5 / 255.0 {
+                0.0
+            } else {
+                lerp(level, 1.0, c.min(1.0))
+            }
+        };
+        Self {
+            r: remap(self.r),
+            g: remap(self.g),
+            b: remap(self.b),
+            w: remap(self.w),
+        }
+    }
+}
+
+/// For pure RGB values, we convert them directly into RGBW without adding any white.
+impl From<RGB> for RGBW {
+    fn from(rgb: RGB) -> Self {
+        Self {
+            r: rgb.r,
+            g: rgb.g,
+            b: rgb.b,
+            w: 0.0,
+        }
+    }
+}
+
+/// Direct conversion from XYZ to RGBW.
+///
+/// The white component is ignored when converting directly from XYZ.
+/// You may use the CIELUV to RGBW conversion instead.
+impl From<XYZ> for RGBW {
+    fn from(xyz: XYZ) -> Self {
+        RGB::from(xyz).into()
+    }
+}
+
+/// Conversion from CIELUV to RGBW is a bit more tricky than with plain RGB,
+/// because we have to derive a value for the white component.
+///
+/// Here, this is done by first performing the CIELUV->XYZ->RGB conversion,
+/// and subsequently using the saturation value from CIELUV to determine
+/// how much of the RGB and white components to use.
+///
+/// The white amount is defined as Y * (1.0 - saturation), whereas the color
+/// RGB components are multiplied by the saturation value.
+///
+/// This calculation produces nice results on SK6812 LEDs with good saturation
+/// for deep reds while avoiding whites that are a mixture of the RGB components.
+impl From<CIELUV> for RGBW {
+    fn from(cieluv: CIELUV) -> Self {
+        // Color saturation from 0..1
+        let saturation = cieluv.saturation() as Real;
+        let whiteness = 1.0 - saturation;
+
+        let xyz = XYZ::from(cieluv);
+
+        let (r, g, b) = xyz.linear_rgb();
+
+        //let rgb_max = r.max(g).max(b);
+
+        let r = r * saturation;
+        let g = g * saturation;
+        let b = b * saturation;
+        let w = xyz.y as Real * whiteness;
+
+        Self {
+            r: (linear_to_srgb(r) as f32).clamp(0.0, 1.0),
+            g: (linear_to_srgb(g) as f32).clamp(0.0, 1.0),
+            b: (linear_to_srgb(b) as f32).clamp(0.0, 1.0),
+            w: (linear_to_srgb(w) as f32).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Conversions from HCL to RGBW is done through CIELUV, then the XYZ color space.
+impl From<HCL> for RGBW {
+    fn from(hcl: HCL) -> Self {
+        CIELUV::from(hcl).into()
+    }
+}
+
+/// Measured light output of one channel of an LED driver, for output devices
+/// that do not follow the sRGB transfer function.
+///
+/// Each point pairs a drive level, such as a PWM duty cycle, with the relative light output
+/// measured at that level, both within `0.0..1.0`. Points must be sorted by drive level,
+/// and the light output must not decrease. The curve is interpolated linearly between them.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ResponseCurve<'a> {
+    points: &'a [(f32, f32)],
+}
+
+impl<'a> ResponseCurve<'a> {
+    pub const fn new(points: &'a [(f32, f32)]) -> Self {
+        Self { points }
+    }
+
+    /// The drive level that produces a light output of `linear` (0.0..1.0).
+    ///
+    /// Light outputs outside of the measured range get the drive level of the nearest point.
+    /// A curve without points returns `linear` unchanged.
+    pub fn drive(&self, linear: f32) -> f32 {
+        let (Some(&(first, low)), Some(&(last, high))) = (self.points.first(), self.points.last())
+        else {
+            return linear;
+        };
+        if linear <= low {
+            return first;
+        }
+        if linear >= high {
+            return last;
+        }
+        for pair in self.points.windows(2) {
+            let [(d0, o0), (d1, o1)] = [pair[0], pair[1]];
+            if linear <= o1 {
+                return lerp(d0, d1, (linear - o0) / (o1 - o0));
+            }
+        }
+        last
+    }
+}
+
+/// Response curves of the red, green and blue channels of an output device,
+/// replacing the sRGB transfer function when driving it.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct DeviceResponse<'a> {
+    pub r: ResponseCurve<'a>,
+    pub g: ResponseCurve<'a>,
+    pub b: ResponseCurve<'a>,
+}
+
+impl DeviceResponse<'_> {
+    /// The drive levels that make the device show `color`.
+    ///
+    /// Each channel is converted to linear light and mapped through its response curve,
+    /// so the result can be quantized directly for the LED driver.
+    pub fn drive_levels(&self, color: &RGB) -> RGB {
+        let drive = |curve: &ResponseCurve, c: f32| curve.drive(srgb_to_linear(c as Real) as f32);
+        RGB {
+            r: drive(&self.r, color.r),
+            g: drive(&self.g, color.g),
+            b: drive(&self.b, color.b),
+        }
+    }
+}
+
+/// Per-channel correction of the color bias of LED strips, applied in linear light.
+///
+/// Bare WS2812 strips, for example, show white with a strong green tint. Each factor scales
+/// the light output of its channel, where 1.0 leaves the channel unchanged. Apply the correction
+/// as the last step before quantizing a color for output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorCorrection {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl Default for ColorCorrection {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl ColorCorrection {
+    /// No correction.
+    pub const NONE: Self = Self {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+    };
+    /// Typical SMD5050 LED strips, such as WS2812. FastLED's `TypicalLEDStrip` (255, 176, 240).
+    pub const TYPICAL_LED_STRIP: Self = Self {
+        r: 1.0,
+        g: 176.0 / 255.0,
+        b: 240.0 / 255.0,
+    };
+    /// Typical 8 mm pixel strings. FastLED's `TypicalPixelString` (255, 224, 140).
+    pub const TYPICAL_PIXEL_STRING: Self = Self {
+        r: 1.0,
+        g: 224.0 / 255.0,
+        b: 140.0 / 255.0,
+    };
+
+    /// Custom correction from 8-bit factors as used by FastLED, where 255 leaves a channel unchanged.
+    pub fn from_u8(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+        }
+    }
+
+    /// Scale each channel of `color` by its factor in linear light.
+    pub fn apply(&self, color: &RGB) -> RGB {
+        let correct = |c: f32, factor: f32| {
+            linear_to_srgb(srgb_to_linear(c as Real) * factor as Real).clamp(0.0, 1.0) as f32
+        };
+        RGB {
+            r: correct(color.r, self.r),
+            g: correct(color.g, self.g),
+            b: correct(color.b, self.b),
+        }
+    }
+}
+
+/// Hue-dependent lightness correction of LED strips, for channels that dim unevenly,
+/// such as red LEDs that fall off faster than green and blue at low brightness.
+///
+/// Each point pairs a hue in degrees with a lightness gain, where 1.0 leaves colors of that hue
+/// unchanged. Points must be sorted by hue. The gain is interpolated linearly between them,
+/// wrapping around the hue circle. Apply the correction in HCL, before quantizing a color
+/// for output, so that strips from different batches can be matched to each other.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct HueCorrection<'a> {
+    points: &'a [(f32, f32)],
+}
+
+impl<'a> HueCorrection<'a> {
+    pub const fn new(points: &'a [(f32, f32)]) -> Self {
+        Self { points }
+    }
+
+    /// The lightness gain at `hue`, in degrees. A correction without points has a gain of 1.0.
+    pub fn gain(&self, hue: f32) -> f32 {
+        let (Some(&(first, first_gain)), Some(&(last, last_gain))) =
+            (self.points.first(), self.points.last())
+        else {
+            return 1.0;
+        };
+        let hue = hue % 360.0;
+        let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+        if hue < first || hue >= last {
+            let span = first + 360.0 - last;
+            let offset = if hue >= last {
+                hue - last
+            } else {
+                hue + 360.0 - last
+            };
+            if span <= 0.0 {
+                return first_gain;
+            }
+            return lerp(last_gain, first_gain, offset / span);
+        }
+        for pair in self.points.windows(2) {
+            let [(h0, g0), (h1, g1)] = [pair[0], pair[1]];
+            if hue < h1 {
+                return lerp(g0, g1, (hue - h0) / (h1 - h0));
+            }
+        }
+        last_gain
+    }
+
+    /// Scale the lightness of `color` by the gain at its hue.
+    ///
+    /// The gain applies fully to dark colors and fades out towards white, so bright colors
+    /// do not clip. Hue and chroma are kept, and grays are left unchanged.
+    pub fn apply(&self, color: &HCL) -> HCL {
+        if color.is_achromatic() {
+            return *color;
+        }
+        let l = color.l as Real;
+        let fade = 1.0 - (l / L_WHITE as Real).clamp(0.0, 1.0);
+        let gain = 1.0 + (self.gain(color.h) as Real - 1.0) * fade;
+        HCL {
+            l: ((l * gain) as f32).min(L_WHITE),
+            ..*color
+        }
+    }
+}
+
+/// HSV color using FastLED's "rainbow" hue mapping, for migrating existing FastLED effect code.
+///
+/// All values are in the range of 0..=255.
+///
+/// * `h` is the hue, where 0 is red, 32 orange, 64 yellow, 96 green, 128 aqua, 160 blue, 192 purple and 224 pink,
+/// * `s` is the saturation, and
+/// * `v` is the value, or brightness.
+///
+/// Converting to RGB reproduces FastLED's `hsv2rgb_rainbow`, including its integer rounding,
+/// with the resulting channel values interpreted as sRGB.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FastLedHsv {
+    pub h: u8,
+    pub s: u8,
+    pub v: u8,
+}
+
+/// FastLED's `hsv2rgb_rainbow`.
+impl From<FastLedHsv> for RGB8 {
+    fn from(hsv: FastLedHsv) -> Self {
+        let offset8 = (hsv.h & 0x1f) << 3;
+        let third = scale8(offset8, 85);
+        let two_thirds = scale8(offset8, 170);
+
+        let mut rgb = match hsv.h >> 5 {
+            0 => [255 - third, third, 0],
+            1 => [171, 85 + third, 0],
+            2 => [171 - two_thirds, 170 + third, 0],
+            3 => [0, 255 - third, third],
+            4 => [0, 171 - two_thirds, 85 + two_thirds],
+            5 => [third, 0, 255 - third],
+            6 => [85 + third, 0, 171 - third],
+            _ => [170 + third, 0, 85 - third],
+        };
+
+        if hsv.s == 0 {
+            rgb = [255; 3];
+        } else if hsv.s != 255 {
+            let desaturation = scale8_video(255 - hsv.s, 255 - hsv.s);
+            let saturation = 255 - desaturation;
+            rgb = rgb.map(|c| scale8(c, saturation) + desaturation);
+        }
+
+        if hsv.v != 255 {
+            let value = scale8_video(hsv.v, hsv.v);
+            rgb = rgb.map(|c| scale8(c, value));
+        }
+
+        let [r, g, b] = rgb;
+        Self { r, g, b }
+    }
+}
+
+impl From<FastLedHsv> for RGB {
+    fn from(hsv: FastLedHsv) -> Self {
+        RGB8::from(hsv).into()
+    }
+}
+
+/// How [`Palette16::color_from_palette`] blends between palette entries.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blend {
+    /// Use the nearest entry below the index, like FastLED's `NOBLEND`.
+    None,
+    /// Blend the 8-bit channel values, like FastLED's `LINEARBLEND`.
+    #[default]
+    Linear,
+    /// Blend in the CIELUV color space, for perceptually even transitions.
+    Perceptual,
+}
+
+/// Palette of 16 colors, sampled like FastLED's `CRGBPalette16`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette16(pub [RGB8; 16]);
+
+impl Palette16 {
+    /// Look up a color the way FastLED's `ColorFromPalette` does.
+    ///
+    /// The upper four bits of `index` select an entry, and the lower four bits blend
+    /// towards the next entry, wrapping around from the last entry to the first.
+    /// The result is scaled by `brightness`, where 255 is full brightness.
+    pub fn color_from_palette(&self, index: u8, brightness: u8, blend: Blend) -> RGB8 {
+        let entry = self.0[(index >> 4) as usize];
+        let next = self.0[((index >> 4) as usize + 1) % 16];
+        let fraction = index & 0x0f;
+
+        let color = match blend {
+            _ if fraction == 0 => entry,
+            Blend::None => entry,
+            Blend::Linear => {
+                let f2 = fraction << 4;
+                let f1 = 255 - f2;
+                let mix = |a: u8, b: u8| scale8(a, f1) + scale8(b, f2);
+                RGB8 {
+                    r: mix(entry.r, next.r),
+                    g: mix(entry.g, next.g),
+                    b: mix(entry.b, next.b),
+                }
+            }
+            Blend::Perceptual => {
+                let start = CIELUV::from(RGB::from(entry));
+                let end = CIELUV::from(RGB::from(next));
+                RGB::from(start.interpolate(&end, fraction as f32 / 16.0)).into()
+            }
+        };
+
+        scale_brightness(color, brightness)
+    }
+}
+
+/// Palette of 256 colors, sampled like FastLED's `CRGBPalette256`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette256(pub [RGB8; 256]);
+
+impl Default for Palette256 {
+    fn default() -> Self {
+        Self([RGB8::default(); 256])
+    }
+}
+
+impl Palette256 {
+    /// Look up a color the way FastLED's `ColorFromPalette` does.
+    ///
+    /// Every `index` has its own entry, so there is nothing to blend.
+    /// The result is scaled by `brightness`, where 255 is full brightness.
+    pub fn color_from_palette(&self, index: u8, brightness: u8) -> RGB8 {
+        scale_brightness(self.0[index as usize], brightness)
+    }
+}
+
+/// Expands a 16 color palette to 256 colors, blending between entries with `Blend::Linear`.
+impl From<Palette16> for Palette256 {
+    fn from(palette: Palette16) -> Self {
+        let mut entries = [RGB8::default(); 256];
+        for (index, entry) in entries.iter_mut().enumerate() {
+            *entry = palette.color_from_palette(index as u8, 255, Blend::Linear);
+        }
+        Self(entries)
+    }
+}
+
+/// Brightness scaling from FastLED's `ColorFromPalette`.
+fn scale_brightness(color: RGB8, brightness: u8) -> RGB8 {
+    match brightness {
+        255 => color,
+        0 => RGB8::default(),
+        _ => RGB8 {
+            r: scale8(color.r, brightness + 1),
+            g: scale8(color.g, brightness + 1),
+            b: scale8(color.b, brightness + 1),
+        },
+    }
+}
+
+/// FastLED's `scale8`, scaling `i` by `scale / 256`.
+#[inline]
+fn scale8(i: u8, scale: u8) -> u8 {
+    ((i as u16 * (1 + scale as u16)) >> 8) as u8
+}
+
+/// FastLED's `scale8_video`, which never scales a non-zero value to zero.
+#[inline]
+fn scale8_video(i: u8, scale: u8) -> u8 {
+    ((i as u16 * scale as u16) >> 8) as u8 + u8::from(i != 0 && scale != 0)
+}
+
+/// Conversions from FastLED HSV to CIELUV is done through the RGB and XYZ color spaces.
+impl From<FastLedHsv> for CIELUV {
+    fn from(hsv: FastLedHsv) -> Self {
+        RGB::from(hsv).into()
+    }
+}
+
+/// CIE 1931 XYZ color space, derived from CIE RGB in an effort to simplify the math.
+/// This color space defines the relationship between the visible spectrum
+/// and the visual sensation of specific colors by human color vision.
+///
+/// Values in the range of 0.0..1.0.
+///
+/// * `x` is a mix of all three RGB curves chosen to be nonnegative,
+/// * `y` is the luminance, and
+/// * `z` is quasi-equal to blue (from CIE RGB).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XYZ {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+// Constants for D65 white point
+const X_REF: Real = 95.047;
+const Y_REF: Real = 100.0;
+const Z_REF: Real = 108.883;
+
+// Based on sRGB Working Space Matrix
+// http://www.brucelindbloom.com/Eqn_RGB_XYZ_Matrix.html
+const LINEAR_RGB_TO_XYZ: [[Real; 3]; 3] = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+
+// sYCC: Amendment 1 to IEC 61966-2-1:1999.
+// Higher conversion precision with seven decimals.
+const XYZ_TO_LINEAR_RGB: [[Real; 3]; 3] = [
+    [3.2406255, -1.5372080, -0.4986286],
+    [-0.9689307, 1.8758561, 0.0415175],
+    [0.0557101, -0.2040211, 1.0570959],
+];
+
+// Display P3, D65 white point and DCI-P3 primaries.
+const P3_TO_XYZ: [[Real; 3]; 3] = [
+    [0.4865709, 0.2656677, 0.1982173],
+    [0.2289746, 0.6917385, 0.0792869],
+    [0.0000000, 0.0451134, 1.0439444],
+];
+const XYZ_TO_P3: [[Real; 3]; 3] = [
+    [2.4934969, -0.9313836, -0.4027108],
+    [-0.8294890, 1.7626641, 0.0236247],
+    [0.0358458, -0.0761724, 0.9568845],
+];
+
+// Adobe RGB (1998), D65 white point.
+// http://www.brucelindbloom.com/Eqn_RGB_XYZ_Matrix.html
+const ADOBE_RGB_TO_XYZ: [[Real; 3]; 3] = [
+    [0.5767309, 0.1855540, 0.1881852],
+    [0.2973769, 0.6273491, 0.0752741],
+    [0.0270343, 0.0706872, 0.9911085],
+];
+const XYZ_TO_ADOBE_RGB: [[Real; 3]; 3] = [
+    [2.0413690, -0.5649464, -0.3446944],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0134474, -0.1183897, 1.0154096],
+];
+
+// Oklab: https://bottosson.github.io/posts/oklab/
+const XYZ_TO_LMS: [[Real; 3]; 3] = [
+    [0.8189330101, 0.3618667424, -0.1288597137],
+    [0.0329845436, 0.9293118715, 0.0361456387],
+    [0.0482003018, 0.2643662691, 0.6338517070],
+];
+const LMS_TO_XYZ: [[Real; 3]; 3] = [
+    [1.2270138511, -0.5577999807, 0.2812561490],
+    [-0.0405801784, 1.1122568696, -0.0716766787],
+    [-0.0763812845, -0.4214819784, 1.5861632204],
+];
+const LMS_TO_OKLAB: [[Real; 3]; 3] = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+const OKLAB_TO_LMS: [[Real; 3]; 3] = [
+    [1.0, 0.3963377774, 0.2158037573],
+    [1.0, -0.1055613458, -0.0638541728],
+    [1.0, -0.0894841775, -1.2914855480],
+];
+
+// CAM16: Li et al., "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS", 2017.
+const M16: [[Real; 3]; 3] = [
+    [0.401288, 0.650173, -0.051461],
+    [-0.250268, 1.204414, 0.045854],
+    [-0.002079, 0.048952, 0.953127],
+];
+const M16_INVERSE: [[Real; 3]; 3] = [
+    [1.86206786, -1.01125463, 0.14918677],
+    [0.38752654, 0.62144744, -0.00897398],
+    [-0.01584150, -0.03412294, 1.04996444],
+];
+
+// XYZ/LUV conversion
+const K: Real = 24389.0 / 27.0;
+const E: Real = 216.0 / 24389.0;
+const U_PRIME_REF: Real = 4.0 * X_REF / (X_REF + 15.0 * Y_REF + 3.0 * Z_REF);
+const V_PRIME_REF: Real = 9.0 * Y_REF / (X_REF + 15.0 * Y_REF + 3.0 * Z_REF);
+
+// Lightness of sRGB white in CIELUV.
+const L_WHITE: f32 = 8.991443;
+
+// Colors with less chroma than this are considered gray, and have no meaningful hue.
+const ACHROMATIC_CHROMA: f32 = 0.001;
+// The same threshold for the Oklab color space.
+const OKLAB_ACHROMATIC_CHROMA: f32 = 0.0002;
+// The same threshold for the CIELAB color space.
+const LAB_ACHROMATIC_CHROMA: f32 = 0.01;
+
+impl XYZ {
+    #[inline]
+    fn u_prime(&self) -> Real {
+        let (x, y, z) = self.real();
+        4.0 * x / (x + 15.0 * y + 3.0 * z)
+    }
+
+    #[inline]
+    fn v_prime(&self) -> Real {
+        let (x, y, z) = self.real();
+        9.0 * y / (x + 15.0 * y + 3.0 * z)
+    }
+
+    #[inline]
+    fn y_ref(&self) -> Real {
+        self.y as Real / Y_REF
+    }
+
+    #[inline]
+    fn real(&self) -> (Real, Real, Real) {
+        (self.x as Real, self.y as Real, self.z as Real)
+    }
+
+    /// Map a color with an absolute luminance in cd/m² onto a fixture with a maximum luminance
+    /// of `max_luminance` cd/m², returning a color relative to the fixture, where Y = 1.0 is its maximum.
+    ///
+    /// Luminance up to a knee at 75% of the fixture maximum is passed through unchanged.
+    /// Above the knee, luminance is compressed smoothly towards the maximum instead of being
+    /// clipped, preserving highlight detail in video-derived content. The chromaticity is kept.
+    pub fn tone_map(&self, max_luminance: f32) -> Self {
+        const KNEE: f32 = 0.75;
+
+        if self.y <= 0.0 || max_luminance <= 0.0 {
+            return Self::default();
+        }
+
+        let knee = KNEE * max_luminance;
+        let headroom = max_luminance - knee;
+        let y = if self.y <= knee {
+            self.y
+        } else {
+            knee + headroom * (1.0 - (-(self.y - knee) / headroom).exp())
+        };
+
+        let scale = y / max_luminance / self.y;
+        Self {
+            x: self.x * scale,
+            y: self.y * scale,
+            z: self.z * scale,
+        }
+    }
+
+    /// Convert from linear sRGB components.
+    #[inline]
+    fn from_linear_rgb(r: Real, g: Real, b: Real) -> Self {
+        Self::from_linear(&LINEAR_RGB_TO_XYZ, [r, g, b])
+    }
+
+    /// Linear RGB components, unclamped.
+    #[inline]
+    fn linear_rgb(&self) -> (Real, Real, Real) {
+        let [r, g, b] = self.linear(&XYZ_TO_LINEAR_RGB);
+        (r, g, b)
+    }
+
+    /// Linear components of any RGB working space, given its matrix to XYZ.
+    #[inline]
+    fn from_linear(matrix: &[[Real; 3]; 3], [r, g, b]: [Real; 3]) -> Self {
+        let [x, y, z] = matrix.map(|[m1, m2, m3]| (m1 * r + m2 * g + m3 * b) as f32);
+        Self { x, y, z }
+    }
+
+    /// Linear components in any RGB working space, given its matrix from XYZ. Unclamped.
+    #[inline]
+    fn linear(&self, matrix: &[[Real; 3]; 3]) -> [Real; 3] {
+        let (x, y, z) = self.real();
+        matrix.map(|[m1, m2, m3]| m1 * x + m2 * y + m3 * z)
+    }
+}
+
+impl Display for XYZ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let x = self.x;
+        let y = self.y;
+        let z = self.z;
+        write!(f, "CIEXYZ X={x:1.2}, Y={y:1.2}, Z={z:1.2}")
+    }
+}
+
+impl From<RGB> for XYZ {
+    fn from(rgb: RGB) -> Self {
+        let r = srgb_to_linear(rgb.r as Real);
+        let g = srgb_to_linear(rgb.g as Real);
+        let b = srgb_to_linear(rgb.b as Real);
+        XYZ::from_linear_rgb(r, g, b)
+    }
+}
+
+impl From<LinearRGB> for XYZ {
+    fn from(rgb: LinearRGB) -> Self {
+        XYZ::from_linear_rgb(rgb.r as Real, rgb.g as Real, rgb.b as Real)
+    }
+}
+
+/// Conversions from HCL to XYZ is done through the CIELUV color space.
+impl From<HCL> for XYZ {
+    fn from(hcl: HCL) -> Self {
+        CIELUV::from(hcl).into()
+    }
+}
+
+impl From<CIELUV> for XYZ {
+    fn from(cieluv: CIELUV) -> Self {
+        if cieluv.l == 0.0 {
+            return XYZ {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+
+        let l = cieluv.l as Real;
+        let u_prime = cieluv.u as Real / (13.0 * l) + 0.19783000664283;
+        let v_prime = cieluv.v as Real / (13.0 * l) + 0.46831999493879;
+
+        let y = lightness_to_y(l);
+
+        let x = y * 9.0 * u_prime / (4.0 * v_prime);
+        let z = y * (12.0 - 3.0 * u_prime - 20.0 * v_prime) / (4.0 * v_prime);
+
+        XYZ {
+            x: x as f32,
+            y: y as f32,
+            z: z as f32,
+        }
+    }
+}
+
+/// Represents a color using CIE 1931 xyY, the chromaticity coordinates `x` and `y` along with
+/// the luminance, as used by LED datasheets and DMX fixtures.
+///
+/// * `x` and `y` are the chromaticity coordinates, with values within `0.0..1.0`, and
+/// * `luminance` is the Y component of [`XYZ`], where sRGB white is `1.0`.
+///
+/// Black has no meaningful chromaticity, and is given the chromaticity of the D65 white point.
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct xyY {
+    pub x: f32,
+    pub y: f32,
+    pub luminance: f32,
+}
+
+impl From<XYZ> for xyY {
+    fn from(xyz: XYZ) -> Self {
+        let (x, y, z) = xyz.real();
+        let sum = x + y + z;
+        if sum <= 0.0 {
+            return Self {
+                x: (X_REF / (X_REF + Y_REF + Z_REF)) as f32,
+                y: (Y_REF / (X_REF + Y_REF + Z_REF)) as f32,
+                luminance: 0.0,
+            };
+        }
+        Self {
+            x: (x / sum) as f32,
+            y: (y / sum) as f32,
+            luminance: xyz.y,
+        }
+    }
+}
+
+impl From<xyY> for XYZ {
+    fn from(xyy: xyY) -> Self {
+        if xyy.y <= 0.0 {
+            return Self::default();
+        }
+        let (x, y, luminance) = (xyy.x as Real, xyy.y as Real, xyy.luminance as Real);
+        Self {
+            x: (x * luminance / y) as f32,
+            y: xyy.luminance,
+            z: ((1.0 - x - y) * luminance / y) as f32,
+        }
+    }
+}
+
+/// Represents a color using the CIE 1976 L*, u*, v* color space.
+///
+/// * `l` is the luminance, with values nominally within `0.0..9.0`,
+/// * `u` is the horizontal axis (green/red), with values approximately `-1.34..2.24`, and
+/// * `v` is the vertical axis (blue/yellow), with values approximately `-1.40..1.22`.
+///
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CIELUV {
+    pub l: f32,
+    pub u: f32,
+    pub v: f32,
+}
+
+impl CIELUV {
+    /// Interpolate between two colors based on a parameter `t` (0.0 to 1.0).
+    /// `t = 0.0` returns the start color, `t = 1.0` returns the end color.
+    /// Any value in between is derived using linear interpolation in the
+    /// CIELUV color space.
+    pub fn interpolate(&self, end: &Self, t: f32) -> Self {
+        Self {
+            l: lerp(self.l, end.l, t),
+            u: lerp(self.u, end.u, t),
+            v: lerp(self.v, end.v, t),
+        }
+    }
+
+    /// Interpolate between two colors by way of `via`, typically a neutral such as white, black or gray.
+    ///
+    /// The first half of `t` (0.0 to 0.5) interpolates from this color to `via`, and the second half
+    /// from `via` to `end`. This produces "A -> white -> B" transitions instead of passing
+    /// through the hues between A and B.
+    pub fn interpolate_through(&self, via: &Self, end: &Self, t: f32) -> Self {
+        if t < 0.5 {
+            self.interpolate(via, t * 2.0)
+        } else {
+            via.interpolate(end, t * 2.0 - 1.0)
+        }
+    }
+
+    /// Evaluate `count` evenly spaced colors from this color to `end`, both included.
+    ///
+    /// Equivalent to calling [`CIELUV::interpolate`] for every step, but adds a precomputed
+    /// delta per step instead, which is considerably cheaper for long strips on slow cores.
+    /// Combine with `.map(RGBW::from)` to render directly to the LEDs.
+    pub fn steps(&self, end: &Self, count: usize) -> GradientSteps {
+        let divisor = count.saturating_sub(1).max(1) as f32;
+        GradientSteps {
+            current: *self,
+            // A single step is the start color.
+            end: if count > 1 { *end } else { *self },
+            delta: Self {
+                l: (end.l - self.l) / divisor,
+                u: (end.u - self.u) / divisor,
+                v: (end.v - self.v) / divisor,
+            },
+            remaining: count,
+        }
+    }
+
+    /// Hue is expressed in degrees between 0.0..360.0.
+    pub fn hue(&self) -> f32 {
+        let mut h = (self.v as Real).atan2(self.u as Real).to_degrees();
+        if h < 0.0 {
+            h += 360.0;
+        }
+        h as f32
+    }
+
+    pub fn chroma(&self) -> f32 {
+        ((self.u as Real).powi(2) + (self.v as Real).powi(2)).sqrt() as f32
+    }
+
+    pub fn saturation(&self) -> f32 {
+        if self.l <= 0.0 {
+            return 0.0;
+        }
+        self.chroma() / self.l
+    }
+
+    /// Perceptual color difference between two colors, CIE 1976 ΔE*uv.
+    ///
+    /// This is the straight-line distance in the CIELUV color space. Note that values scale
+    /// with the lightness range of [`CIELUV`], where white is close to 9.0 rather than 100.0.
+    pub fn delta_e(&self, other: &Self) -> f32 {
+        ((self.l - other.l).powi(2) + (self.u - other.u).powi(2) + (self.v - other.v).powi(2))
+            .sqrt()
+    }
+
+    /// Returns true if this color can be represented in sRGB without clipping.
+    pub fn in_gamut(&self) -> bool {
+        const EPSILON: Real = 0.0001;
+        let (r, g, b) = XYZ::from(*self).linear_rgb();
+        [r, g, b]
+            .iter()
+            .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c))
+    }
+
+    /// Returns a color with the lightness `l` and the same hue as this one.
+    ///
+    /// The chroma is kept if possible. Otherwise, it is reduced to [`max_chroma`],
+    /// producing the nearest in-gamut color with exactly that lightness.
+    /// Lightness values above that of white can not be represented and result in a gray.
+    pub fn with_lightness(&self, l: f32) -> Self {
+        let color = Self { l, ..*self };
+        let chroma = color.chroma();
+        let max = max_chroma(l, color.hue());
+        if chroma <= max {
+            return color;
+        }
+        color.scale_chroma(max / chroma)
+    }
+
+    /// Scale the chroma by `factor`, keeping the lightness and hue.
+    ///
+    /// The chroma is capped at the sRGB gamut boundary, see [`max_chroma`],
+    /// so that boosted colors keep their hue instead of being clipped per RGB channel.
+    pub fn boost_saturation(&self, factor: f32) -> Self {
+        let chroma = self.chroma();
+        if chroma <= 0.0 {
+            return *self;
+        }
+        let boosted = (chroma * factor).min(max_chroma(self.l, self.hue()));
+        self.scale_chroma(boosted / chroma)
+    }
+
+    /// Increase the chroma of muted colors more than that of already saturated ones.
+    ///
+    /// An `amount` of 0.0 leaves the color unchanged. The boost is weighted by how far
+    /// the color is from the gamut boundary at its lightness and hue, so colors close to
+    /// [`max_chroma`] are barely affected. Negative amounts mute colors the same way.
+    pub fn vibrance(&self, amount: f32) -> Self {
+        let max = max_chroma(self.l, self.hue());
+        if max <= 0.0 {
+            return *self;
+        }
+        let relative = (self.chroma() / max).min(1.0);
+        self.boost_saturation(1.0 + amount * (1.0 - relative))
+    }
+
+    /// Cap the CIE lightness L* at `max`, for installations with a brightness ceiling.
+    ///
+    /// `max` ranges from `0.0..100.0`, where 100.0 is sRGB white, so a `max` of 80.0 caps colors
+    /// at 80% of the perceived brightness of white. Brighter colors are darkened to `max` with
+    /// their hue and saturation kept, rather than scaling the output channels afterwards.
+    pub fn limit_lightness(&self, max: f32) -> Self {
+        let max = lstar_to_lightness(max.max(0.0) as Real) as f32;
+        if self.l <= max {
+            return *self;
+        }
+        let factor = max / self.l;
+        Self {
+            l: max,
+            u: self.u * factor,
+            v: self.v * factor,
+        }
+    }
+
+    /// Counteract the perceived desaturation of dimmed colors (the Hunt effect)
+    /// by boosting chroma more the darker the color is.
+    ///
+    /// A `strength` of 0.0 leaves the color unchanged. At 1.0, the chroma of a color is scaled
+    /// by up to 2.0 as its lightness approaches black. The boost is capped at the gamut boundary,
+    /// see [`CIELUV::boost_saturation`].
+    pub fn compensate_dimming(&self, strength: f32) -> Self {
+        let darkness = 1.0 - self.relative_lightness();
+        self.boost_saturation(1.0 + strength * darkness)
+    }
+
+    /// Map this color onto a duotone, where its lightness selects a color
+    /// between `shadows` (black) and `highlights` (white).
+    pub fn duotone(&self, shadows: &Self, highlights: &Self) -> Self {
+        shadows.interpolate(highlights, self.relative_lightness())
+    }
+
+    /// Map this color onto a tritone, where its lightness selects a color
+    /// between `shadows` (black), `midtones` and `highlights` (white).
+    pub fn tritone(&self, shadows: &Self, midtones: &Self, highlights: &Self) -> Self {
+        let t = self.relative_lightness() * 2.0;
+        if t < 1.0 {
+            shadows.interpolate(midtones, t)
+        } else {
+            midtones.interpolate(highlights, t - 1.0)
+        }
+    }
+
+    /// Lightness relative to sRGB white, from 0.0..1.0.
+    #[inline]
+    fn relative_lightness(&self) -> f32 {
+        (self.l / L_WHITE).clamp(0.0, 1.0)
+    }
+
+    /// Like [`CIELUV::boost_saturation`], but attenuated within the protected `region`.
+    pub fn boost_saturation_protected(&self, factor: f32, region: &ProtectedRegion) -> Self {
+        self.boost_saturation(lerp(factor, 1.0, region.weight(self)))
+    }
+
+    /// Like [`CIELUV::vibrance`], but attenuated within the protected `region`.
+    pub fn vibrance_protected(&self, amount: f32, region: &ProtectedRegion) -> Self {
+        self.vibrance(amount * (1.0 - region.weight(self)))
+    }
+
+    #[inline]
+    fn scale_chroma(&self, factor: f32) -> Self {
+        Self {
+            l: self.l,
+            u: self.u * factor,
+            v: self.v * factor,
+        }
+    }
+}
+
+impl Display for CIELUV {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let l = self.l;
+        let u = self.u;
+        let v = self.v;
+        write!(f, "CIELUV L*={l:1.2}, u*={u:1.2}, v*={v:1.2}")
+    }
+}
+
+impl From<XYZ> for CIELUV {
+    // Verified here: http://www.brucelindbloom.com/index.html?Eqn_XYZ_to_Luv.html
+    // Introduced constants due to http://www.brucelindbloom.com/LContinuity.html
+    fn from(xyz: XYZ) -> Self {
+        if xyz.x == 0.0 && xyz.y == 0.0 && xyz.z == 0.0 {
+            return Self {
+                l: 0.0,
+                u: 0.0,
+                v: 0.0,
+            };
+        }
+
+        let u_prime = xyz.u_prime();
+        let v_prime = xyz.v_prime();
+        let y_ref = xyz.y_ref();
+
+        let l = lstar(y_ref);
+
+        Self {
+            l: l as f32,
+            u: (13.0 * l * (u_prime - U_PRIME_REF)) as f32,
+            v: (13.0 * l * (v_prime - V_PRIME_REF)) as f32,
+        }
+    }
+}
+
+/// Conversions to CIELUV from RGB is done through the XYZ color space.
+impl From<RGB> for CIELUV {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
+    }
+}
+
+/// Conversions to HCL from RGB is done by first converting to CIELUV, then converting to HCL.
+impl From<HCL> for CIELUV {
+    fn from(hcl: HCL) -> Self {
+        let h_rad = (hcl.h as Real).to_radians(); // Convert hue to radians
+        let u = hcl.c as Real * h_rad.cos();
+        let v = hcl.c as Real * h_rad.sin();
+        CIELUV {
+            l: hcl.l,
+            u: u as f32,
+            v: v as f32,
+        }
+    }
+}
+
+/// CIELCh, also known as HCL or CIELUVch, is a cylindrical representation of the CIELUV color space.
+///
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
+/// * `c` is the chromaticity, ranging from `0.0..1.0`, and
+/// * `l` is the luminance, ranging from `0.0..1.0`.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HCL {
+    pub h: f32,
+    pub c: f32,
+    pub l: f32,
+}
+
+impl HCL {
+    /// Returns true if the color is a gray, without any meaningful hue.
+    pub fn is_achromatic(&self) -> bool {
+        self.c < ACHROMATIC_CHROMA
+    }
+
+    /// The hue in degrees, or `None` if the color is a gray.
+    pub fn hue(&self) -> Option<f32> {
+        if self.is_achromatic() {
+            None
+        } else {
+            Some(self.h)
+        }
+    }
+
+    /// The hue in degrees, or `default` if the color is a gray.
+    ///
+    /// When interpolating through a gray, pass the hue of the other end point
+    /// to avoid spinning through arbitrary hues.
+    pub fn hue_or(&self, default: f32) -> f32 {
+        self.hue().unwrap_or(default)
+    }
+
+    /// Quantize lightness, chroma and hue into `levels` steps each, producing banded, poster-like colors.
+    ///
+    /// Lightness is quantized into even steps of CIE L* between black and white, hue into equal
+    /// angles around the hue circle, and chroma relative to [`max_chroma`] at the quantized
+    /// lightness and hue. Fewer than two levels are treated as two.
+    pub fn posterize(&self, levels: u8) -> Self {
+        let steps = levels.max(2) as f32;
+        let quantize = |x: f32| (x.clamp(0.0, 1.0) * (steps - 1.0)).round() / (steps - 1.0);
+
+        let lstar = quantize(lightness_to_lstar(self.l as Real) as f32 / 100.0) * 100.0;
+        let l = lstar_to_lightness(lstar as Real) as f32;
+        let h = ((self.h / 360.0 * steps).round() % steps) * 360.0 / steps;
+        let max = max_chroma(l, h);
+        let c = if max > 0.0 {
+            quantize(self.c / max) * max
+        } else {
+            0.0
+        };
+
+        Self { h, c, l }
+    }
+
+    /// Rotate the hue by `degrees` while keeping the perceived brightness constant.
+    ///
+    /// The lightness is left as is, and the chroma is reduced to [`max_chroma`] if the
+    /// new hue can not hold it. Otherwise, clipping in RGB would change the brightness.
+    pub fn rotate_hue(&self, degrees: f32) -> Self {
+        let mut h = (self.h + degrees) % 360.0;
+        if h < 0.0 {
+            h += 360.0;
+        }
+        Self {
+            h,
+            c: self.c.min(max_chroma(self.l, h)),
+            l: self.l,
+        }
+    }
+}
+
+impl From<RGB> for HCL {
+    fn from(rgb: RGB) -> Self {
+        CIELUV::from(rgb).into()
+    }
+}
+
+impl From<XYZ> for HCL {
+    fn from(xyz: XYZ) -> Self {
+        CIELUV::from(xyz).into()
+    }
+}
+
+/// Grays have no meaningful hue, so their hue is set to 0.0, see [`HCL::hue`].
+impl From<CIELUV> for HCL {
+    fn from(cieluv: CIELUV) -> Self {
+        let c = cieluv.chroma();
+        HCL {
+            h: if c < ACHROMATIC_CHROMA {
+                0.0
+            } else {
+                cieluv.hue()
+            },
+            c,
+            l: cieluv.l,
+        }
+    }
+}
+
+/// Iterator over evenly spaced colors of a gradient, see [`CIELUV::steps`].
+#[derive(Debug, Clone)]
+pub struct GradientSteps {
+    current: CIELUV,
+    end: CIELUV,
+    delta: CIELUV,
+    remaining: usize,
+}
+
+impl Iterator for GradientSteps {
+    type Item = CIELUV;
+
+    fn next(&mut self) -> Option<CIELUV> {
+        match self.remaining {
+            0 => None,
+            // Return the end color exactly, without accumulated rounding errors.
+            1 => {
+                self.remaining = 0;
+                Some(self.end)
+            }
+            _ => {
+                self.remaining -= 1;
+                let color = self.current;
+                self.current.l += self.delta.l;
+                self.current.u += self.delta.u;
+                self.current.v += self.delta.v;
+                Some(color)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for GradientSteps {}
+
+/// Buffer of CIELUV colors stored as a struct of arrays, with one array per component.
+///
+/// Compared to an array of [`CIELUV`], operations that run over one component at a time
+/// can be vectorized by the compiler, and make better use of the cache for very large installations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorBuffer<const N: usize> {
+    pub l: [f32; N],
+    pub u: [f32; N],
+    pub v: [f32; N],
+}
+
+impl<const N: usize> Default for ColorBuffer<N> {
+    fn default() -> Self {
+        Self {
+            l: [0.0; N],
+            u: [0.0; N],
+            v: [0.0; N],
+        }
+    }
+}
+
+impl<const N: usize> ColorBuffer<N> {
+    /// Color at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> CIELUV {
+        CIELUV {
+            l: self.l[index],
+            u: self.u[index],
+            v: self.v[index],
+        }
+    }
+
+    /// Replace the color at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, color: CIELUV) {
+        self.l[index] = color.l;
+        self.u[index] = color.u;
+        self.v[index] = color.v;
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = CIELUV> + '_ {
+        (0..N).map(|i| self.get(i))
+    }
+
+    /// Interpolate every color in the buffer towards the corresponding color in `end`,
+    /// see [`CIELUV::interpolate`].
+    pub fn interpolate(&self, end: &Self, t: f32) -> Self {
+        let mut buffer = Self::default();
+        for (out, (a, b)) in [
+            (&mut buffer.l, (&self.l, &end.l)),
+            (&mut buffer.u, (&self.u, &end.u)),
+            (&mut buffer.v, (&self.v, &end.v)),
+        ] {
+            for i in 0..N {
+                out[i] = lerp(a[i], b[i], t);
+            }
+        }
+        buffer
+    }
+
+    /// Convert the buffer for output to RGBW LEDs.
+    pub fn to_rgbw(&self) -> [RGBW; N] {
+        core::array::from_fn(|i| self.get(i).into())
+    }
+}
+
+impl<const N: usize> From<[CIELUV; N]> for ColorBuffer<N> {
+    fn from(colors: [CIELUV; N]) -> Self {
+        Self {
+            l: colors.map(|c| c.l),
+            u: colors.map(|c| c.u),
+            v: colors.map(|c| c.v),
+        }
+    }
+}
+
+impl<const N: usize> From<[RGB; N]> for ColorBuffer<N> {
+    fn from(colors: [RGB; N]) -> Self {
+        colors.map(CIELUV::from).into()
+    }
+}
+
+impl<const N: usize> From<&ColorBuffer<N>> for [CIELUV; N] {
+    fn from(buffer: &ColorBuffer<N>) -> Self {
+        core::array::from_fn(|i| buffer.get(i))
+    }
+}
+
+/// A region of hues and saturations where saturation changes are attenuated, see
+/// [`CIELUV::boost_saturation_protected`] and [`CIELUV::vibrance_protected`].
+///
+/// The default region covers typical skin tones, which otherwise turn orange
+/// when boosting photo or video derived content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProtectedRegion {
+    /// Center of the region, as a hue in degrees.
+    pub hue: f32,
+    /// Distance from the center hue, in degrees, at which the protection has faded out.
+    pub hue_width: f32,
+    /// Saturation up to which colors are protected, see [`CIELUV::saturation`].
+    /// The protection fades out at twice this saturation.
+    pub saturation: f32,
+    /// How much changes are attenuated at the center of the region,
+    /// from 0.0 (not at all) to 1.0 (completely).
+    pub strength: f32,
+}
+
+impl Default for ProtectedRegion {
+    fn default() -> Self {
+        Self {
+            hue: 38.0,
+            hue_width: 20.0,
+            saturation: 1.2,
+            strength: 0.75,
+        }
+    }
+}
+
+impl ProtectedRegion {
+    /// How much changes to `color` are attenuated, from 0.0 outside of the region to `strength` within it.
+    pub fn weight(&self, color: &CIELUV) -> f32 {
+        if color.chroma() < ACHROMATIC_CHROMA || self.hue_width <= 0.0 || self.saturation <= 0.0 {
+            return 0.0;
+        }
+        let hue = 1.0 - hue_distance(color.hue(), self.hue) / self.hue_width;
+        let saturation = 2.0 - color.saturation() / self.saturation;
+        self.strength * hue.clamp(0.0, 1.0) * saturation.clamp(0.0, 1.0)
+    }
+}
+
+/// Compact HCL color with 8 bits per component, for storing large palettes
+/// and animation keyframes in limited flash or RAM.
+///
+/// * `h` is the hue, where 0..=255 covers the full circle of `0.0..360.0` degrees,
+/// * `c` is the chroma relative to [`max_chroma`] at the given lightness and hue, where 255 is the most colorful, and
+/// * `l` is the CIE lightness L*, where 0 is black and 255 is white, in perceptually even steps.
+///
+/// Because chroma is relative to the gamut boundary, every value converts to a color within sRGB.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HCL8 {
+    pub h: u8,
+    pub c: u8,
+    pub l: u8,
+}
+
+impl From<HCL> for HCL8 {
+    fn from(hcl: HCL) -> Self {
+        let lstar = lightness_to_lstar(hcl.l as Real) as f32;
+        let l = (lstar / 100.0 * 255.0).clamp(0.0, 255.0).round() as u8;
+        let h = ((hcl.h / 360.0 * 256.0).round() as u32 % 256) as u8;
+
+        let decoded = HCL::from(HCL8 { h, c: 0, l });
+        let max = max_chroma(decoded.l, decoded.h);
+        let c = if max > 0.0 {
+            (hcl.c / max * 255.0).clamp(0.0, 255.0).round() as u8
+        } else {
+            0
+        };
+
+        Self { h, c, l }
+    }
+}
+
+impl From<HCL8> for HCL {
+    fn from(hcl: HCL8) -> Self {
+        let h = hcl.h as f32 * 360.0 / 256.0;
+        let l = lstar_to_lightness(hcl.l as Real * 100.0 / 255.0) as f32;
+        let c = hcl.c as f32 / 255.0 * max_chroma(l, h);
+        Self { h, c, l }
+    }
+}
+
+impl From<XYZ> for HCL8 {
+    fn from(xyz: XYZ) -> Self {
+        HCL::from(xyz).into()
+    }
+}
+
+impl From<HCL8> for XYZ {
+    fn from(hcl: HCL8) -> Self {
+        HCL::from(hcl).into()
+    }
+}
+
+/// HSLuv, a cylindrical representation of CIELUV with saturation relative to the sRGB gamut.
+///
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
+/// * `s` is the saturation, ranging from `0.0..100.0`, where 100.0 is the most saturated color
+///   representable in sRGB for the given hue and lightness, see [`max_chroma`], and
+/// * `l` is the CIE lightness L*, ranging from `0.0..100.0`, where 100.0 is sRGB white,
+///   see [`linear_to_lstar`].
+///
+/// Any combination of values within these ranges converts to RGB without clipping,
+/// which makes this color space well suited for user interface controls and LED effects.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HSLuv {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+impl From<HCL> for HSLuv {
+    fn from(hcl: HCL) -> Self {
+        let max = max_chroma(hcl.l, hcl.h) as Real;
+        Self {
+            h: hcl.h,
+            s: if max > 0.0 {
+                (hcl.c as Real / max * 100.0) as f32
+            } else {
+                0.0
+            },
+            l: lightness_to_lstar(hcl.l as Real) as f32,
+        }
+    }
+}
+
+impl From<HSLuv> for HCL {
+    fn from(hsluv: HSLuv) -> Self {
+        let l = lstar_to_lightness(hsluv.l as Real) as f32;
+        Self {
+            h: hsluv.h,
+            c: (hsluv.s as Real / 100.0 * max_chroma(l, hsluv.h) as Real) as f32,
+            l,
+        }
+    }
+}
+
+impl From<CIELUV> for HSLuv {
+    fn from(cieluv: CIELUV) -> Self {
+        HCL::from(cieluv).into()
+    }
+}
+
+impl From<HSLuv> for CIELUV {
+    fn from(hsluv: HSLuv) -> Self {
+        HCL::from(hsluv).into()
+    }
+}
+
+impl From<RGB> for HSLuv {
+    fn from(rgb: RGB) -> Self {
+        HCL::from(rgb).into()
+    }
+}
+
+impl From<HSLuv> for RGB {
+    fn from(hsluv: HSLuv) -> Self {
+        HCL::from(hsluv).into()
+    }
+}
+
+impl From<XYZ> for HSLuv {
+    fn from(xyz: XYZ) -> Self {
+        HCL::from(xyz).into()
+    }
+}
+
+impl From<HSLuv> for XYZ {
+    fn from(hsluv: HSLuv) -> Self {
+        HCL::from(hsluv).into()
+    }
+}
+
+/// HPLuv, a variant of [`HSLuv`] where saturation is relative to the chroma
+/// that is representable at every hue, see [`max_safe_chroma`].
+///
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`,
+/// * `p` is the saturation, ranging from `0.0..100.0`, and
+/// * `l` is the CIE lightness L*, ranging from `0.0..100.0`, where 100.0 is sRGB white,
+///   see [`linear_to_lstar`].
+///
+/// Rotating the hue at constant saturation and lightness never clips, so colors stay
+/// equally saturated and bright through the rotation. The price is that only pastel colors
+/// can be represented; saturations above 100.0 are valid for some hues, but may clip.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HPLuv {
+    pub h: f32,
+    pub p: f32,
+    pub l: f32,
+}
+
+impl From<HCL> for HPLuv {
+    fn from(hcl: HCL) -> Self {
+        let max = max_safe_chroma(hcl.l) as Real;
+        Self {
+            h: hcl.h,
+            p: if max > 0.0 {
+                (hcl.c as Real / max * 100.0) as f32
+            } else {
+                0.0
+            },
+            l: lightness_to_lstar(hcl.l as Real) as f32,
+        }
+    }
+}
+
+impl From<HPLuv> for HCL {
+    fn from(hpluv: HPLuv) -> Self {
+        let l = lstar_to_lightness(hpluv.l as Real) as f32;
+        Self {
+            h: hpluv.h,
+            c: (hpluv.p as Real / 100.0 * max_safe_chroma(l) as Real) as f32,
+            l,
+        }
+    }
+}
+
+impl From<CIELUV> for HPLuv {
+    fn from(cieluv: CIELUV) -> Self {
+        HCL::from(cieluv).into()
+    }
+}
+
+impl From<HPLuv> for CIELUV {
+    fn from(hpluv: HPLuv) -> Self {
+        HCL::from(hpluv).into()
+    }
+}
+
+impl From<RGB> for HPLuv {
+    fn from(rgb: RGB) -> Self {
+        HCL::from(rgb).into()
+    }
+}
+
+impl From<HPLuv> for RGB {
+    fn from(hpluv: HPLuv) -> Self {
+        HCL::from(hpluv).into()
+    }
+}
+
+impl From<XYZ> for HPLuv {
+    fn from(xyz: XYZ) -> Self {
+        HCL::from(xyz).into()
+    }
+}
+
+impl From<HPLuv> for XYZ {
+    fn from(hpluv: HPLuv) -> Self {
+        HCL::from(hpluv).into()
+    }
+}
+
+/// Represents a color using the Oklab color space.
+///
+/// * `l` is the perceived lightness, with values within `0.0..1.0`,
+/// * `a` is the horizontal axis (green/red), with values approximately `-0.23..0.28`, and
+/// * `b` is the vertical axis (blue/yellow), with values approximately `-0.31..0.20`.
+///
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Oklab {
+    /// Interpolate between two colors based on a parameter `t` (0.0 to 1.0).
+    /// `t = 0.0` returns the start color, `t = 1.0` returns the end color.
+    /// Any value in between is derived using linear interpolation in the
+    /// Oklab color space.
+    pub fn interpolate(&self, end: &Self, t: f32) -> Self {
+        Self {
+            l: lerp(self.l, end.l, t),
+            a: lerp(self.a, end.a, t),
+            b: lerp(self.b, end.b, t),
+        }
+    }
+}
+
+impl Display for Oklab {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let l = self.l;
+        let a = self.a;
+        let b = self.b;
+        write!(f, "Oklab L={l:1.2}, a={a:1.2}, b={b:1.2}")
+    }
+}
+
+impl From<XYZ> for Oklab {
+    fn from(xyz: XYZ) -> Self {
+        let (x, y, z) = xyz.real();
+        let lms = XYZ_TO_LMS.map(|[m1, m2, m3]| (m1 * x + m2 * y + m3 * z).cbrt());
+        let [l, a, b] = LMS_TO_OKLAB.map(|m| m[0] * lms[0] + m[1] * lms[1] + m[2] * lms[2]);
+        Self {
+            l: l as f32,
+            a: a as f32,
+            b: b as f32,
+        }
+    }
+}
+
+impl From<Oklab> for XYZ {
+    fn from(oklab: Oklab) -> Self {
+        let (l, a, b) = (oklab.l as Real, oklab.a as Real, oklab.b as Real);
+        let lms = OKLAB_TO_LMS.map(|[m1, m2, m3]| (m1 * l + m2 * a + m3 * b).powi(3));
+        let [x, y, z] = LMS_TO_XYZ.map(|m| m[0] * lms[0] + m[1] * lms[1] + m[2] * lms[2]);
+        Self {
+            x: x as f32,
+            y: y as f32,
+            z: z as f32,
+        }
+    }
+}
+
+/// Conversions between Oklab and RGB are done through the XYZ color space.
+impl From<RGB> for Oklab {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
+    }
+}
+
+impl From<Oklab> for RGB {
+    fn from(oklab: Oklab) -> Self {
+        XYZ::from(oklab).into()
+    }
+}
+
+/// Oklch is a cylindrical representation of the Oklab color space, as [`HCL`] is of CIELUV.
+///
+/// * `l` is the perceived lightness, ranging from `0.0..1.0`,
+/// * `c` is the chroma, ranging from `0.0` to approximately `0.32`, and
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl Oklch {
+    /// Returns true if the color is a gray, without any meaningful hue.
+    pub fn is_achromatic(&self) -> bool {
+        self.c < OKLAB_ACHROMATIC_CHROMA
+    }
+
+    /// The hue in degrees, or `None` if the color is a gray.
+    pub fn hue(&self) -> Option<f32> {
+        if self.is_achromatic() {
+            None
+        } else {
+            Some(self.h)
+        }
+    }
+
+    /// The hue in degrees, or `default` if the color is a gray, see [`HCL::hue_or`].
+    pub fn hue_or(&self, default: f32) -> f32 {
+        self.hue().unwrap_or(default)
+    }
+
+    /// Rotate the hue by `degrees`, keeping lightness and chroma.
+    ///
+    /// Unlike [`HCL::rotate_hue`], the chroma is not reduced to fit the gamut at the new hue.
+    pub fn rotate_hue(&self, degrees: f32) -> Self {
+        let mut h = (self.h + degrees) % 360.0;
+        if h < 0.0 {
+            h += 360.0;
+        }
+        Self { h, ..*self }
+    }
+}
+
+/// Grays have no meaningful hue, so their hue is set to 0.0, see [`Oklch::hue`].
+impl From<Oklab> for Oklch {
+    fn from(oklab: Oklab) -> Self {
+        let (a, b) = (oklab.a as Real, oklab.b as Real);
+        let c = (a * a + b * b).sqrt();
+        let mut h = b.atan2(a).to_degrees();
+        if c < OKLAB_ACHROMATIC_CHROMA as Real {
+            h = 0.0;
+        } else if h < 0.0 {
+            h += 360.0;
+        }
+        Self {
+            l: oklab.l,
+            c: c as f32,
+            h: h as f32,
+        }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(oklch: Oklch) -> Self {
+        let (sin, cos) = (oklch.h as Real).to_radians().sin_cos();
+        Self {
+            l: oklch.l,
+            a: (oklch.c as Real * cos) as f32,
+            b: (oklch.c as Real * sin) as f32,
+        }
+    }
+}
+
+impl From<XYZ> for Oklch {
+    fn from(xyz: XYZ) -> Self {
+        Oklab::from(xyz).into()
+    }
+}
+
+impl From<Oklch> for XYZ {
+    fn from(oklch: Oklch) -> Self {
+        Oklab::from(oklch).into()
+    }
+}
+
+impl From<RGB> for Oklch {
+    fn from(rgb: RGB) -> Self {
+        Oklab::from(rgb).into()
+    }
+}
+
+impl From<Oklch> for RGB {
+    fn from(oklch: Oklch) -> Self {
+        Oklab::from(oklch).into()
+    }
+}
+
+/// The white component is derived through CIELUV, see the CIELUV to RGBW conversion.
+impl From<Oklch> for RGBW {
+    fn from(oklch: Oklch) -> Self {
+        CIELUV::from(XYZ::from(oklch)).into()
+    }
+}
+
+/// Represents a color using the CIELAB (CIE 1976 L\*a\*b\*) color space, relative to the D65 white point.
+///
+/// * `l` is the CIE lightness L*, ranging from `0.0..100.0`, where 100.0 is sRGB white,
+/// * `a` is the green/red axis, with values approximately `-86.0..98.0` within sRGB, and
+/// * `b` is the blue/yellow axis, with values approximately `-108.0..95.0` within sRGB.
+///
+/// Unlike [`CIELUV::l`], `l` is on the usual CIE scale.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CIELAB {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl CIELAB {
+    /// Interpolate between two colors based on a parameter `t` (0.0 to 1.0).
+    /// `t = 0.0` returns the start color, `t = 1.0` returns the end color.
+    /// Any value in between is derived using linear interpolation in the
+    /// CIELAB color space.
+    pub fn interpolate(&self, end: &Self, t: f32) -> Self {
+        Self {
+            l: lerp(self.l, end.l, t),
+            a: lerp(self.a, end.a, t),
+            b: lerp(self.b, end.b, t),
+        }
+    }
+
+    /// The hue angle in degrees, ranging from `0.0..360.0`.
+    pub fn hue(&self) -> f32 {
+        LChab::from(*self).h
+    }
+
+    /// The chroma, the distance from the neutral axis.
+    pub fn chroma(&self) -> f32 {
+        LChab::from(*self).c
+    }
+}
+
+impl Display for CIELAB {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let l = self.l;
+        let a = self.a;
+        let b = self.b;
+        write!(f, "CIELAB L={l:1.2}, a={a:1.2}, b={b:1.2}")
+    }
+}
+
+// Verified here: http://www.brucelindbloom.com/index.html?Eqn_XYZ_to_Lab.html
+impl From<XYZ> for CIELAB {
+    fn from(xyz: XYZ) -> Self {
+        let (x, y, z) = xyz.real();
+        let f = |t: Real| {
+            if t > E {
+                t.cbrt()
+            } else {
+                (K * t + 16.0) / 116.0
+            }
+        };
+        let fx = f(x * Y_REF / X_REF);
+        let fy = f(y);
+        let fz = f(z * Y_REF / Z_REF);
+        Self {
+            l: (116.0 * fy - 16.0) as f32,
+            a: (500.0 * (fx - fy)) as f32,
+            b: (200.0 * (fy - fz)) as f32,
+        }
+    }
+}
+
+impl From<CIELAB> for XYZ {
+    fn from(lab: CIELAB) -> Self {
+        let fy = (lab.l as Real + 16.0) / 116.0;
+        let fx = fy + lab.a as Real / 500.0;
+        let fz = fy - lab.b as Real / 200.0;
+        let f_inverse = |f: Real| {
+            if f.powi(3) > E {
+                f.powi(3)
+            } else {
+                (116.0 * f - 16.0) / K
+            }
+        };
+        Self {
+            x: (f_inverse(fx) * X_REF / Y_REF) as f32,
+            y: lstar_inverse(lab.l as Real) as f32,
+            z: (f_inverse(fz) * Z_REF / Y_REF) as f32,
+        }
+    }
+}
+
+/// Conversions between CIELAB and RGB are done through the XYZ color space.
+impl From<RGB> for CIELAB {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
+    }
+}
+
+impl From<CIELAB> for RGB {
+    fn from(lab: CIELAB) -> Self {
+        XYZ::from(lab).into()
+    }
+}
+
+/// LChab is a cylindrical representation of the CIELAB color space, as [`HCL`] is of CIELUV.
+///
+/// * `l` is the CIE lightness L*, ranging from `0.0..100.0`,
+/// * `c` is the chroma, ranging from `0.0` to approximately `134.0` within sRGB, and
+/// * `h` is the hue, expressed as an angle and ranging from `0.0..360.0`.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LChab {
     pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl LChab {
+    /// Returns true if the color is a gray, without any meaningful hue.
+    pub fn is_achromatic(&self) -> bool {
+        self.c < LAB_ACHROMATIC_CHROMA
+    }
+
+    /// The hue in degrees, or `None` if the color is a gray.
+    pub fn hue(&self) -> Option<f32> {
+        if self.is_achromatic() {
+            None
+        } else {
+            Some(self.h)
+        }
+    }
+
+    /// The hue in degrees, or `default` if the color is a gray, see [`HCL::hue_or`].
+    pub fn hue_or(&self, default: f32) -> f32 {
+        self.hue().unwrap_or(default)
+    }
+}
+
+/// Grays have no meaningful hue, so their hue is set to 0.0, see [`LChab::hue`].
+impl From<CIELAB> for LChab {
+    fn from(lab: CIELAB) -> Self {
+        let (a, b) = (lab.a as Real, lab.b as Real);
+        let c = (a * a + b * b).sqrt();
+        let mut h = b.atan2(a).to_degrees();
+        if c < LAB_ACHROMATIC_CHROMA as Real {
+            h = 0.0;
+        } else if h < 0.0 {
+            h += 360.0;
+        }
+        Self {
+            l: lab.l,
+            c: c as f32,
+            h: h as f32,
+        }
+    }
+}
+
+impl From<LChab> for CIELAB {
+    fn from(lch: LChab) -> Self {
+        let (sin, cos) = (lch.h as Real).to_radians().sin_cos();
+        Self {
+            l: lch.l,
+            a: (lch.c as Real * cos) as f32,
+            b: (lch.c as Real * sin) as f32,
+        }
+    }
+}
+
+impl From<XYZ> for LChab {
+    fn from(xyz: XYZ) -> Self {
+        CIELAB::from(xyz).into()
+    }
+}
+
+impl From<LChab> for XYZ {
+    fn from(lch: LChab) -> Self {
+        CIELAB::from(lch).into()
+    }
+}
+
+impl From<RGB> for LChab {
+    fn from(rgb: RGB) -> Self {
+        CIELAB::from(rgb).into()
+    }
+}
+
+impl From<LChab> for RGB {
+    fn from(lch: LChab) -> Self {
+        CIELAB::from(lch).into()
+    }
+}
+
+/// Surround of the viewing field in [`ViewingConditions`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surround {
+    /// Surfaces viewed in a lit room.
+    #[default]
+    Average,
+    /// Displays viewed in a dim room, such as a television.
+    Dim,
+    /// Light sources viewed in the dark, such as LED installations at night.
+    Dark,
+}
+
+/// Viewing conditions of the CAM16 color appearance model, see [`CAM16UCS`].
+///
+/// The adopted white point is D65, as everywhere else in this crate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewingConditions {
+    rgb_d: [Real; 3],
+    fl: Real,
+    n: Real,
+    z: Real,
+    nbb: Real,
+    c: Real,
+    nc: Real,
+    aw: Real,
+}
+
+impl Default for ViewingConditions {
+    /// Typical conditions for viewing sRGB content, with an adapting luminance of
+    /// 64 lux / π / 5, a background luminance of 20 and an average surround.
+    fn default() -> Self {
+        Self::new(64.0 / core::f32::consts::PI / 5.0, 20.0, Surround::Average)
+    }
+}
+
+impl ViewingConditions {
+    /// Viewing conditions with an `adapting_luminance` in cd/m², a `background_luminance`
+    /// relative to white at 100.0, and the `surround` of the viewing field.
+    pub fn new(adapting_luminance: f32, background_luminance: f32, surround: Surround) -> Self {
+        let la = adapting_luminance as Real;
+        let (f, c) = match surround {
+            Surround::Average => (1.0, 0.69),
+            Surround::Dim => (0.9, 0.59),
+            Surround::Dark => (0.8, 0.525),
+        };
+
+        let rgb_w = M16.map(|[m1, m2, m3]| m1 * X_REF + m2 * Y_REF + m3 * Z_REF);
+        let d = (f * (1.0 - (1.0 / 3.6) * ((-la - 42.0) / 92.0).exp())).clamp(0.0, 1.0);
+        let rgb_d = rgb_w.map(|w| d * Y_REF / w + 1.0 - d);
+
+        let k = 1.0 / (5.0 * la + 1.0);
+        let k4 = k.powi(4);
+        let fl = k4 * la + 0.1 * (1.0 - k4).powi(2) * (5.0 * la).cbrt();
+        let n = background_luminance as Real / Y_REF;
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 / n.powf(0.2);
+
+        let [r, g, b] = [0, 1, 2].map(|i| cam16_compress(fl, rgb_d[i] * rgb_w[i]));
+        let aw = (2.0 * r + g + 0.05 * b) * nbb;
+
+        Self {
+            rgb_d,
+            fl,
+            n,
+            z,
+            nbb,
+            c,
+            nc: f,
+            aw,
+        }
+    }
+}
+
+/// Represents a color in CAM16-UCS, the uniform color space of the CAM16 color appearance model.
+///
+/// * `j` is the lightness J', with values within `0.0..100.0`,
+/// * `a` is the horizontal axis (green/red), and
+/// * `b` is the vertical axis (blue/yellow), both with values approximately within `-50.0..50.0`.
+///
+/// Appearance depends on the [`ViewingConditions`], which are given explicitly with
+/// [`CAM16UCS::from_xyz`] and [`CAM16UCS::to_xyz`]. The `From` conversions use the default conditions.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CAM16UCS {
+    pub j: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl CAM16UCS {
+    /// Convert from XYZ, as seen under `conditions`.
+    pub fn from_xyz(xyz: &XYZ, conditions: &ViewingConditions) -> Self {
+        let vc = conditions;
+        let (x, y, z) = xyz.real();
+        let rgb = M16.map(|[m1, m2, m3]| (m1 * x + m2 * y + m3 * z) * Y_REF);
+        let [r, g, b] = [0, 1, 2].map(|i| cam16_compress(vc.fl, vc.rgb_d[i] * rgb[i]));
+
+        let a = (11.0 * r - 12.0 * g + b) / 11.0;
+        let b_ = (r + g - 2.0 * b) / 9.0;
+        let u = (20.0 * r + 20.0 * g + 21.0 * b) / 20.0;
+        let p2 = (40.0 * r + 20.0 * g + b) / 20.0;
+        let h = b_.atan2(a);
+
+        let j = 100.0 * (p2 * vc.nbb / vc.aw).max(0.0).powf(vc.c * vc.z);
+        let t = cam16_p1(vc, h) * (a * a + b_ * b_).sqrt() / (u + 0.305);
+        let chroma =
+            (1.64 - (0.29 as Real).powf(vc.n)).powf(0.73) * t.powf(0.9) * (j / 100.0).sqrt();
+        let m = chroma * vc.fl.powf(0.25);
+
+        let j = 1.7 * j / (1.0 + 0.007 * j);
+        let m = (1.0 + 0.0228 * m).ln() / 0.0228;
+        Self {
+            j: j as f32,
+            a: (m * h.cos()) as f32,
+            b: (m * h.sin()) as f32,
+        }
+    }
+
+    /// Convert to XYZ, as seen under `conditions`.
+    pub fn to_xyz(&self, conditions: &ViewingConditions) -> XYZ {
+        let vc = conditions;
+        let (j, a, b) = (self.j as Real, self.a as Real, self.b as Real);
+        if j <= 0.0 {
+            return XYZ::default();
+        }
+
+        let j = j / (1.7 - 0.007 * j);
+        let m = ((a * a + b * b).sqrt() * 0.0228).exp_m1() / 0.0228;
+        let h = b.atan2(a);
+        let chroma = m / vc.fl.powf(0.25);
+
+        let alpha = chroma / (j / 100.0).sqrt();
+        let t = (alpha / (1.64 - (0.29 as Real).powf(vc.n)).powf(0.73)).powf(1.0 / 0.9);
+        let p1 = cam16_p1(vc, h);
+        let p2 = vc.aw * (j / 100.0).powf(1.0 / (vc.c * vc.z)) / vc.nbb;
+
+        let (sin, cos) = h.sin_cos();
+        let gamma = 23.0 * (p2 + 0.305) * t / (23.0 * p1 + 11.0 * t * cos + 108.0 * t * sin);
+        let (a, b) = (gamma * cos, gamma * sin);
+        let rgb_a = [
+            (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0,
+            (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0,
+            (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0,
+        ];
+        let rgb = [0, 1, 2].map(|i| cam16_decompress(vc.fl, rgb_a[i]) / vc.rgb_d[i] / Y_REF);
+        let [x, y, z] = M16_INVERSE.map(|m| m[0] * rgb[0] + m[1] * rgb[1] + m[2] * rgb[2]);
+
+        XYZ {
+            x: x as f32,
+            y: y as f32,
+            z: z as f32,
+        }
+    }
+
+    /// Interpolate between two colors based on a parameter `t` (0.0 to 1.0), see [`CIELUV::interpolate`].
+    pub fn interpolate(&self, end: &Self, t: f32) -> Self {
+        Self {
+            j: lerp(self.j, end.j, t),
+            a: lerp(self.a, end.a, t),
+            b: lerp(self.b, end.b, t),
+        }
+    }
+
+    /// Perceptual color difference between two colors, the straight-line distance in CAM16-UCS.
+    pub fn delta_e(&self, other: &Self) -> f32 {
+        let (dj, da, db) = (self.j - other.j, self.a - other.a, self.b - other.b);
+        (dj * dj + da * da + db * db).sqrt()
+    }
+}
+
+/// The `From` conversions use the default [`ViewingConditions`].
+impl From<XYZ> for CAM16UCS {
+    fn from(xyz: XYZ) -> Self {
+        Self::from_xyz(&xyz, &ViewingConditions::default())
+    }
+}
+
+impl From<CAM16UCS> for XYZ {
+    fn from(cam16: CAM16UCS) -> Self {
+        cam16.to_xyz(&ViewingConditions::default())
+    }
+}
+
+impl From<RGB> for CAM16UCS {
+    fn from(rgb: RGB) -> Self {
+        XYZ::from(rgb).into()
+    }
+}
+
+impl From<CAM16UCS> for RGB {
+    fn from(cam16: CAM16UCS) -> Self {
+        XYZ::from(cam16).into()
+    }
+}
+
+/// Eccentricity and chromatic induction factors of CAM16, for the hue angle `h` in radians.
+fn cam16_p1(conditions: &ViewingConditions, h: Real) -> Real {
+    let mut degrees = h.to_degrees();
+    if degrees < 20.14 {
+        degrees += 360.0;
+    }
+    let e_t = 0.25 * ((degrees.to_radians() + 2.0).cos() + 3.8);
+    50000.0 / 13.0 * e_t * conditions.nc * conditions.nbb
+}
+
+/// Post-adaptation non-linear response compression of CAM16.
+fn cam16_compress(fl: Real, c: Real) -> Real {
+    let f = (fl * c.abs() / 100.0).powf(0.42);
+    c.signum() * 400.0 * f / (f + 27.13)
+}
+
+/// Inverse of [`cam16_compress`].
+fn cam16_decompress(fl: Real, c: Real) -> Real {
+    let base = (27.13 * c.abs() / (400.0 - c.abs())).max(0.0);
+    c.signum() * 100.0 / fl * base.powf(1.0 / 0.42)
+}
+
+/// A color in any of the supported color spaces, for configuration and APIs
+/// where the color space of the input is not known at compile time.
+///
+/// With the `serde` feature, colors are serialized tagged with their variant,
+/// e.g. `{"Rgb":{"r":1.0,"g":0.5,"b":0.0}}` in JSON.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Rgb(RGB),
+    Xyz(XYZ),
+    Luv(CIELUV),
+    Hcl(HCL),
+    Hsluv(HSLuv),
+    Hpluv(HPLuv),
+    Oklab(Oklab),
+    Oklch(Oklch),
+    Lab(CIELAB),
+    Lchab(LChab),
+    Hsl(HSL),
+    Hsv(HSV),
+    Hwb(HWB),
+    Cmyk(CMYK),
+    XyY(xyY),
+    Cam16Ucs(CAM16UCS),
+    LinearRgb(LinearRGB),
+    P3(P3),
+    AdobeRgb(AdobeRGB),
+}
+
+impl Color {
+    /// Convert this color into the color space `T`, e.g. `color.to::<CIELUV>()`.
+    pub fn to<T: ColorSpace>(&self) -> T {
+        match *self {
+            Color::Rgb(rgb) => rgb.convert(),
+            Color::Xyz(xyz) => xyz.convert(),
+            Color::Luv(cieluv) => cieluv.convert(),
+            Color::Hcl(hcl) => hcl.convert(),
+            Color::Hsluv(hsluv) => hsluv.convert(),
+            Color::Hpluv(hpluv) => hpluv.convert(),
+            Color::Oklab(oklab) => oklab.convert(),
+            Color::Oklch(oklch) => oklch.convert(),
+            Color::Lab(lab) => lab.convert(),
+            Color::Lchab(lch) => lch.convert(),
+            Color::Hsl(hsl) => hsl.convert(),
+            Color::Hsv(hsv) => hsv.convert(),
+            Color::Hwb(hwb) => hwb.convert(),
+            Color::Cmyk(cmyk) => cmyk.convert(),
+            Color::XyY(xyy) => xyy.convert(),
+            Color::Cam16Ucs(cam16) => cam16.convert(),
+            Color::LinearRgb(rgb) => rgb.convert(),
+            Color::P3(p3) => p3.convert(),
+            Color::AdobeRgb(adobe) => adobe.convert(),
+        }
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::Rgb(RGB::default())
+    }
+}
+
+impl From<XYZ> for Color {
+    fn from(xyz: XYZ) -> Self {
+        Color::Xyz(xyz)
+    }
+}
+
+impl From<Color> for XYZ {
+    fn from(color: Color) -> Self {
+        color.to()
+    }
+}
+
+impl From<RGB> for Color {
+    fn from(rgb: RGB) -> Self {
+        Color::Rgb(rgb)
+    }
+}
+
+impl From<CIELUV> for Color {
+    fn from(cieluv: CIELUV) -> Self {
+        Color::Luv(cieluv)
+    }
+}
+
+impl From<HCL> for Color {
+    fn from(hcl: HCL) -> Self {
+        Color::Hcl(hcl)
+    }
+}
+
+impl From<HSLuv> for Color {
+    fn from(hsluv: HSLuv) -> Self {
+        Color::Hsluv(hsluv)
+    }
+}
+
+impl From<HPLuv> for Color {
+    fn from(hpluv: HPLuv) -> Self {
+        Color::Hpluv(hpluv)
+    }
+}
+
+impl From<Oklab> for Color {
+    fn from(oklab: Oklab) -> Self {
+        Color::Oklab(oklab)
+    }
+}
+
+impl From<Oklch> for Color {
+    fn from(oklch: Oklch) -> Self {
+        Color::Oklch(oklch)
+    }
+}
+
+impl From<CIELAB> for Color {
+    fn from(lab: CIELAB) -> Self {
+        Color::Lab(lab)
+    }
+}
+
+impl From<LChab> for Color {
+    fn from(lch: LChab) -> Self {
+        Color::Lchab(lch)
+    }
+}
+
+impl From<HSL> for Color {
+    fn from(hsl: HSL) -> Self {
+        Color::Hsl(hsl)
+    }
+}
+
+impl From<HSV> for Color {
+    fn from(hsv: HSV) -> Self {
+        Color::Hsv(hsv)
+    }
+}
+
+impl From<HWB> for Color {
+    fn from(hwb: HWB) -> Self {
+        Color::Hwb(hwb)
+    }
+}
+
+impl From<CMYK> for Color {
+    fn from(cmyk: CMYK) -> Self {
+        Color::Cmyk(cmyk)
+    }
+}
+
+impl From<xyY> for Color {
+    fn from(xyy: xyY) -> Self {
+        Color::XyY(xyy)
+    }
+}
+
+impl From<CAM16UCS> for Color {
+    fn from(cam16: CAM16UCS) -> Self {
+        Color::Cam16Ucs(cam16)
+    }
+}
+
+impl From<LinearRGB> for Color {
+    fn from(rgb: LinearRGB) -> Self {
+        Color::LinearRgb(rgb)
+    }
+}
+
+impl From<P3> for Color {
+    fn from(p3: P3) -> Self {
+        Color::P3(p3)
+    }
+}
+
+impl From<AdobeRGB> for Color {
+    fn from(adobe: AdobeRGB) -> Self {
+        Color::AdobeRgb(adobe)
+    }
+}
+
+/// The maximum chroma representable in sRGB for a given CIELUV lightness `l` and `hue` in degrees.
+///
+/// Colors with a chroma up to this value can be converted to RGB without clipping.
+///
+/// The gamut boundary is found analytically: for a fixed lightness, each RGB channel
+/// reaching either 0.0 or 1.0 traces a straight line in the u*v* plane.
+/// The maximum chroma is the distance to the nearest of these lines along the hue angle.
+pub fn max_chroma(l: f32, hue: f32) -> f32 {
+    if l <= 0.0 {
+        return 0.0;
+    }
+
+    let (sin, cos) = (hue as Real).to_radians().sin_cos();
+    let mut max = Real::MAX;
+
+    for (a, b, n) in gamut_bounds(l as Real) {
+        let chroma = n / (a * cos + b * sin);
+        if chroma >= 0.0 && chroma < max {
+            max = chroma;
+        }
+    }
+
+    if max == Real::MAX {
+        0.0
+    } else {
+        max as f32
+    }
+}
+
+/// The maximum chroma representable in sRGB for a given CIELUV lightness `l` at every hue.
+///
+/// This is the radius of the largest circle around the gray axis that fits within the gamut,
+/// the smallest perpendicular distance to any of the lines described in [`max_chroma`].
+pub fn max_safe_chroma(l: f32) -> f32 {
+    if l <= 0.0 {
+        return 0.0;
+    }
+
+    gamut_bounds(l as Real)
+        .iter()
+        .map(|(a, b, n)| n.abs() / (a * a + b * b).sqrt())
+        .fold(Real::MAX, Real::min) as f32
+}
+
+/// Lines in the u*v* plane where each linear RGB channel reaches 0.0 or 1.0 at CIELUV lightness `l`.
+///
+/// Each line is `(a, b, n)`, such that the chroma at which the line is crossed along a hue angle is
+/// `n / (a * cos(hue) + b * sin(hue))`.
+fn gamut_bounds(l: Real) -> [(Real, Real, Real); 6] {
+    let y = lightness_to_y(l);
+    let mut bounds = [(0.0, 0.0, 0.0); 6];
+
+    for (i, [m1, m2, m3]) in XYZ_TO_LINEAR_RGB.into_iter().enumerate() {
+        for (j, bound) in [0.0, 1.0].into_iter().enumerate() {
+            // The channel equals `bound` where a * u' + b * v' + d = 0.
+            let a = y * (9.0 * m1 - 3.0 * m3);
+            let b = y * (4.0 * m2 - 20.0 * m3) - 4.0 * bound;
+            let d = 12.0 * m3 * y;
+            let n = -13.0 * l * (a * U_PRIME_REF + b * V_PRIME_REF + d);
+            bounds[i * 2 + j] = (a, b, n);
+        }
+    }
+
+    bounds
+}
+
+/// A palette of 8-bit colors borrowed from elsewhere, typically a `static` array in flash,
+/// that can be sampled smoothly without copying it into RAM.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteRef<'a> {
+    entries: &'a [RGB8],
+}
+
+impl<'a> PaletteRef<'a> {
+    pub const fn new(entries: &'a [RGB8]) -> Self {
+        Self { entries }
+    }
+
+    pub const fn entries(&self) -> &'a [RGB8] {
+        self.entries
+    }
+
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sample the palette at `t` (0.0 to 1.0), where `t = 0.0` returns the first entry
+    /// and `t = 1.0` returns the last entry. Values in between are interpolated
+    /// between the two nearest entries in the CIELUV color space.
+    ///
+    /// An empty palette samples as black.
+    pub fn sample(&self, t: f32) -> CIELUV {
+        let Some(last) = self.entries.len().checked_sub(1) else {
+            return CIELUV::default();
+        };
+
+        let position = t.clamp(0.0, 1.0) * last as f32;
+        let index = (position as usize).min(last);
+        let start = CIELUV::from(RGB::from(self.entries[index]));
+        if index == last {
+            return start;
+        }
+
+        let end = CIELUV::from(RGB::from(self.entries[index + 1]));
+        start.interpolate(&end, position - index as f32)
+    }
+}
+
+/// An operation on a color that can be used as a trait object,
+/// so that effect chains can be assembled at runtime, see [`Pipeline`].
+///
+/// Implemented for [`ColorOp`] and for any `Fn(CIELUV) -> CIELUV`.
+pub trait DynColorOp {
+    /// Apply the operation to a color.
+    fn apply(&self, color: CIELUV) -> CIELUV;
+}
+
+impl<F: Fn(CIELUV) -> CIELUV> DynColorOp for F {
+    fn apply(&self, color: CIELUV) -> CIELUV {
+        self(color)
+    }
+}
+
+/// Operations provided by this crate, in a form that can be loaded from configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorOp {
+    /// See [`CIELUV::boost_saturation`].
+    BoostSaturation(f32),
+    /// See [`CIELUV::vibrance`].
+    Vibrance(f32),
+    /// See [`HCL::rotate_hue`].
+    RotateHue(f32),
+    /// See [`CIELUV::with_lightness`].
+    Lightness(f32),
+    /// See [`CIELUV::limit_lightness`].
+    LimitLightness(f32),
+    /// See [`CIELUV::compensate_dimming`].
+    CompensateDimming(f32),
+    /// See [`HCL::posterize`].
+    Posterize(u8),
+}
+
+impl DynColorOp for ColorOp {
+    fn apply(&self, color: CIELUV) -> CIELUV {
+        match *self {
+            ColorOp::BoostSaturation(factor) => color.boost_saturation(factor),
+            ColorOp::Vibrance(amount) => color.vibrance(amount),
+            ColorOp::RotateHue(degrees) => HCL::from(color).rotate_hue(degrees).into(),
+            ColorOp::Lightness(l) => color.with_lightness(l),
+            ColorOp::LimitLightness(max) => color.limit_lightness(max),
+            ColorOp::CompensateDimming(strength) => color.compensate_dimming(strength),
+            ColorOp::Posterize(levels) => HCL::from(color).posterize(levels).into(),
+        }
+    }
+}
+
+/// A chain of operations applied in order, borrowed from storage owned by the caller.
+///
+/// A pipeline is itself a [`DynColorOp`], so pipelines can be nested.
+#[derive(Clone, Copy)]
+pub struct Pipeline<'a> {
+    ops: &'a [&'a dyn DynColorOp],
+}
+
+impl<'a> Pipeline<'a> {
+    pub const fn new(ops: &'a [&'a dyn DynColorOp]) -> Self {
+        Self { ops }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl DynColorOp for Pipeline<'_> {
+    fn apply(&self, color: CIELUV) -> CIELUV {
+        self.ops.iter().fold(color, |color, op| op.apply(color))
+    }
+}
+
+/// Difference between two gradients, see [`compare_gradients`].
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct GradientComparison {
+    /// Largest [`CIELUV::delta_e`] between corresponding samples.
+    pub max_delta_e: f32,
+    /// Average [`CIELUV::delta_e`] between corresponding samples.
+    pub mean_delta_e: f32,
+}
+
+/// Compare two gradients by sampling both at `samples` evenly spaced positions from 0.0 to 1.0,
+/// for instance to check that a baked palette still matches the original design.
+///
+/// Gradients are given as functions from a position to a color, such as
+/// `|t| start.interpolate(&end, t)` or `|t| palette.sample(t)`.
+pub fn compare_gradients(
+    a: impl Fn(f32) -> CIELUV,
+    b: impl Fn(f32) -> CIELUV,
+    samples: usize,
+) -> GradientComparison {
+    let samples = samples.max(2);
+    let mut comparison = GradientComparison::default();
+
+    for i in 0..samples {
+        let t = i as f32 / (samples - 1) as f32;
+        let delta_e = a(t).delta_e(&b(t));
+        comparison.max_delta_e = comparison.max_delta_e.max(delta_e);
+        comparison.mean_delta_e += delta_e / samples as f32;
+    }
+
+    comparison
 }
 
-impl From<RGB> for HCL {
-    fn from(rgb: RGB) -> Self {
-        CIELUV::from(rgb).into()
+/// Visible banding in a gradient, see [`detect_banding`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Banding {
+    /// The quantized output stays the same from `start` to `end` while the gradient changes.
+    Plateau { start: f32, end: f32 },
+    /// The quantized output jumps by more than one level at `position`.
+    Jump { position: f32, levels: u16 },
+}
+
+/// Detect banding by sampling a gradient at `samples` evenly spaced positions from 0.0 to 1.0,
+/// and quantizing the RGB output to `bits` per channel, as the target hardware does.
+///
+/// Yields a [`Banding::Plateau`] for every run of more than `max_run` identical outputs
+/// where the unquantized gradient changes, and a [`Banding::Jump`] for every step between
+/// adjacent samples of more than one level in any channel.
+/// Use as many samples as the gradient has LEDs.
+///
+/// Gradients are given as functions from a position to a color, see [`compare_gradients`].
+pub fn detect_banding<F: Fn(f32) -> CIELUV>(
+    gradient: F,
+    samples: usize,
+    bits: u8,
+    max_run: usize,
+) -> impl Iterator<Item = Banding> {
+    let samples = samples.max(2);
+    let sample = RGB::from(gradient(0.0));
+    let mut detector = BandingDetector {
+        gradient,
+        samples,
+        levels: ((1u32 << bits.clamp(1, 16)) - 1) as f32,
+        max_run,
+        index: 0,
+        run_start: 0,
+        run_color: sample,
+        previous: [0; 3],
+        pending: None,
+    };
+    detector.previous = detector.quantize(&sample);
+    detector
+}
+
+struct BandingDetector<F> {
+    gradient: F,
+    samples: usize,
+    levels: f32,
+    max_run: usize,
+    index: usize,
+    run_start: usize,
+    run_color: RGB,
+    previous: [u16; 3],
+    pending: Option<Banding>,
+}
+
+impl<F: Fn(f32) -> CIELUV> BandingDetector<F> {
+    fn position_of(&self, index: usize) -> f32 {
+        index as f32 / (self.samples - 1) as f32
+    }
+
+    fn quantize(&self, rgb: &RGB) -> [u16; 3] {
+        [rgb.r, rgb.g, rgb.b].map(|c| (c.clamp(0.0, 1.0) * self.levels).round() as u16)
+    }
+
+    /// Plateau for the run ending before `end`, if it is too long and the gradient changes within it.
+    fn plateau(&self, end: usize, color: &RGB) -> Option<Banding> {
+        let changes = [
+            self.run_color.r - color.r,
+            self.run_color.g - color.g,
+            self.run_color.b - color.b,
+        ]
+        .iter()
+        .any(|d| d.abs() > f32::EPSILON);
+
+        (end - self.run_start > self.max_run && changes).then(|| Banding::Plateau {
+            start: self.position_of(self.run_start),
+            end: self.position_of(end - 1),
+        })
     }
 }
 
-impl From<XYZ> for HCL {
-    fn from(xyz: XYZ) -> Self {
-        CIELUV::from(xyz).into()
+impl<F: Fn(f32) -> CIELUV> Iterator for BandingDetector<F> {
+    type Item = Banding;
+
+    fn next(&mut self) -> Option<Banding> {
+        loop {
+            if let Some(banding) = self.pending.take() {
+                return Some(banding);
+            }
+            self.index += 1;
+            if self.index > self.samples {
+                return None;
+            }
+
+            let index = self.index;
+            if index == self.samples {
+                // End of the gradient, close the last run.
+                let last = RGB::from((self.gradient)(1.0));
+                return self.plateau(index, &last);
+            }
+
+            let color = RGB::from((self.gradient)(self.position_of(index)));
+            let quantized = self.quantize(&color);
+            if quantized == self.previous {
+                continue;
+            }
+
+            let levels = (0..3)
+                .map(|i| quantized[i].abs_diff(self.previous[i]))
+                .max()
+                .unwrap_or(0);
+            let last = RGB::from((self.gradient)(self.position_of(index - 1)));
+            let plateau = self.plateau(index, &last);
+            self.previous = quantized;
+            self.run_start = index;
+            self.run_color = color;
+
+            self.pending = (levels > 1).then(|| Banding::Jump {
+                position: self.position_of(index),
+                levels,
+            });
+            if plateau.is_some() {
+                return plateau;
+            }
+        }
     }
 }
 
-impl From<CIELUV> for HCL {
-    fn from(cieluv: CIELUV) -> Self {
-        HCL {
-            h: cieluv.hue(),
-            c: cieluv.chroma(),
-            l: cieluv.l,
+/// How [`estimate_white_balance`] estimates the color of the light in a scene.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhiteBalanceMethod {
+    /// Assume the scene averages to gray.
+    #[default]
+    GrayWorld,
+    /// Assume the brightest value of each channel is the color of the light.
+    MaxRgb,
+}
+
+/// Correction for the color cast of a scene, as gains for each channel in linear light.
+///
+/// This is a von Kries style chromatic adaptation performed directly on the sRGB primaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WhiteBalance {
+    /// Gains for the linear red, green and blue channels.
+    pub gains: [f32; 3],
+}
+
+impl Default for WhiteBalance {
+    fn default() -> Self {
+        Self { gains: [1.0; 3] }
+    }
+}
+
+impl WhiteBalance {
+    /// Apply the correction to a color.
+    pub fn apply(&self, rgb: &RGB) -> RGB {
+        let correct = |c: f32, gain: f32| {
+            (linear_to_srgb(srgb_to_linear(c as Real) * gain as Real) as f32).clamp(0.0, 1.0)
+        };
+        RGB {
+            r: correct(rgb.r, self.gains[0]),
+            g: correct(rgb.g, self.gains[1]),
+            b: correct(rgb.b, self.gains[2]),
+        }
+    }
+}
+
+/// Estimate the white balance correction for a buffer of colors, such as a camera frame.
+///
+/// The color of the light is estimated in linear light using `method`. The returned gains
+/// turn that color into a neutral gray of the same luminance.
+pub fn estimate_white_balance(colors: &[RGB], method: WhiteBalanceMethod) -> WhiteBalance {
+    let mut light: [Real; 3] = [0.0; 3];
+    for color in colors {
+        let linear = [color.r, color.g, color.b].map(|c| srgb_to_linear(c as Real));
+        for (l, c) in light.iter_mut().zip(linear) {
+            match method {
+                WhiteBalanceMethod::GrayWorld => *l += c,
+                WhiteBalanceMethod::MaxRgb => *l = l.max(c),
+            }
+        }
+    }
+
+    let [r, g, b] = light;
+    let luminance = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    WhiteBalance {
+        gains: light.map(|c| if c > 0.0 { (luminance / c) as f32 } else { 1.0 }),
+    }
+}
+
+/// Pair every entry in the `start` palette with the entry in the `end` palette
+/// that is closest to it in hue, for use with [`interpolate_palettes`].
+///
+/// Entries are paired greedily in order, and each entry in `end` is used only once.
+/// After the call, `pairs[i]` holds the index into `end` that `start[i]` is paired with.
+///
+/// Panics if the palettes and `pairs` are not of equal length.
+pub fn pair_palettes_by_hue(start: &[CIELUV], end: &[CIELUV], pairs: &mut [usize]) {
+    assert_eq!(start.len(), end.len(), "palettes must be of equal length");
+    assert_eq!(
+        start.len(),
+        pairs.len(),
+        "pairs must be as long as the palettes"
+    );
+
+    for i in 0..start.len() {
+        let hue = start[i].hue();
+        let mut nearest = None;
+        let mut nearest_distance = f32::MAX;
+
+        for (j, candidate) in end.iter().enumerate() {
+            if pairs[..i].contains(&j) {
+                continue;
+            }
+            let distance = hue_distance(hue, candidate.hue());
+            if distance < nearest_distance {
+                nearest = Some(j);
+                nearest_distance = distance;
+            }
+        }
+
+        pairs[i] = nearest.unwrap_or(i);
+    }
+}
+
+/// Interpolate between two palettes of equal length, writing the intermediate palette to `out`.
+///
+/// Each entry is interpolated in the CIELUV color space, as with [`CIELUV::interpolate`].
+/// Entries are paired by index when `pairs` is `None`; otherwise `start[i]` is
+/// interpolated towards `end[pairs[i]]`, see [`pair_palettes_by_hue`].
+///
+/// Panics if the palettes, `pairs` and `out` are not of equal length.
+pub fn interpolate_palettes(
+    start: &[CIELUV],
+    end: &[CIELUV],
+    pairs: Option<&[usize]>,
+    t: f32,
+    out: &mut [CIELUV],
+) {
+    assert_eq!(start.len(), end.len(), "palettes must be of equal length");
+    assert_eq!(
+        start.len(),
+        out.len(),
+        "output must be as long as the palettes"
+    );
+
+    for (i, color) in out.iter_mut().enumerate() {
+        let j = match pairs {
+            Some(pairs) => pairs[i],
+            None => i,
+        };
+        *color = start[i].interpolate(&end[j], t);
+    }
+}
+
+/// Stretch or compress the lightness range of a palette in place, so that its darkest entry
+/// ends up at `min` and its lightest at `max`, e.g. to adapt a palette designed for a monitor
+/// to a dim LED installation.
+///
+/// Lightness is remapped as CIE L*, so `min` and `max` range from 0.0 to 100.0, see [`linear_to_lstar`],
+/// and the entries stay perceptually evenly spaced. Hue is kept, and chroma is kept relative
+/// to [`max_chroma`] so that saturated entries stay saturated without leaving the gamut.
+/// Grays stay gray. If all entries have the same lightness, they are moved to the middle
+/// of the target range.
+pub fn stretch_palette_lightness(palette: &mut [CIELUV], min: f32, max: f32) {
+    let (min, max) = (min as Real, max as Real);
+    let (low, high) = palette
+        .iter()
+        .map(|color| lightness_to_lstar(color.l as Real))
+        .fold((Real::MAX, Real::MIN), |(low, high), l| {
+            (low.min(l), high.max(l))
+        });
+    let range = high - low;
+
+    for color in palette.iter_mut() {
+        let hcl = HCL::from(*color);
+        let lstar = if range > 0.0 {
+            min + (lightness_to_lstar(hcl.l as Real) - low) / range * (max - min)
+        } else {
+            (min + max) / 2.0
+        };
+        let l = (lstar_to_lightness(lstar.clamp(0.0, 100.0)) as f32).min(L_WHITE);
+
+        let before = max_chroma(hcl.l, hcl.h);
+        let c = if hcl.is_achromatic() {
+            hcl.c
+        } else if before > 0.0 {
+            (hcl.c / before).min(1.0) * max_chroma(l, hcl.h)
+        } else {
+            0.0
+        };
+
+        *color = HCL { h: hcl.h, c, l }.into();
+    }
+}
+
+/// Relative luminance Y for a given CIELUV lightness.
+#[inline]
+fn lightness_to_y(l: Real) -> Real {
+    Y_REF * lstar_inverse(l)
+}
+
+/// CIE lightness L* (0.0..100.0) of a CIELUV lightness on this crate's scale.
+#[inline]
+fn lightness_to_lstar(l: Real) -> Real {
+    lstar(lightness_to_y(l))
+}
+
+/// CIELUV lightness on this crate's scale of a CIE lightness L* (0.0..100.0).
+#[inline]
+fn lstar_to_lightness(l: Real) -> Real {
+    lstar(lstar_inverse(l) / Y_REF)
+}
+
+/// CIE lightness L* (0.0..100.0) of a linear relative luminance (0.0..1.0).
+///
+/// L* is perceptually uniform, so this is the function to use for brightness sliders
+/// and fades: a slider position `p` (0.0..1.0) maps to a linear brightness factor
+/// of `lstar_to_linear(p * 100.0)`.
+///
+/// Note that [`CIELUV::l`] is on this crate's own lightness scale, where white is about 9.0.
+pub fn linear_to_lstar(linear: f32) -> f32 {
+    lstar(linear as Real) as f32
+}
+
+/// Linear relative luminance (0.0..1.0) of a CIE lightness L* (0.0..100.0),
+/// the inverse of [`linear_to_lstar`].
+pub fn lstar_to_linear(lstar: f32) -> f32 {
+    lstar_inverse(lstar as Real) as f32
+}
+
+/// CIE lightness function.
+#[inline]
+fn lstar(y: Real) -> Real {
+    if y > E {
+        116.0 * y.powf(1.0 / 3.0) - 16.0
+    } else {
+        K * y
+    }
+}
+
+/// Inverse of the CIE lightness function.
+#[inline]
+fn lstar_inverse(l: Real) -> Real {
+    if l > K * E {
+        ((l + 16.0) / 116.0).powi(3)
+    } else {
+        l / K
+    }
+}
+
+/// Count how many colors fall into each of `N` equally wide hue bins, starting at 0 degrees.
+///
+/// Grays have no meaningful hue and are not counted. `N` must be at least one.
+#[cfg(any(test, feature = "std"))]
+pub fn hue_histogram<const N: usize>(colors: &[RGB]) -> [u32; N] {
+    const { assert!(N > 0, "a histogram needs at least one bin") };
+    let mut bins = [0; N];
+    for color in colors {
+        let cieluv = CIELUV::from(*color);
+        if cieluv.chroma() < ACHROMATIC_CHROMA {
+            continue;
+        }
+        let bin = (cieluv.hue() / 360.0 * N as f32) as usize;
+        bins[bin.min(N - 1)] += 1;
+    }
+    bins
+}
+
+/// Count how many colors fall into each of `N` equally wide bins of CIE L*, from black to white.
+///
+/// `N` must be at least one.
+#[cfg(any(test, feature = "std"))]
+pub fn lightness_histogram<const N: usize>(colors: &[RGB]) -> [u32; N] {
+    const { assert!(N > 0, "a histogram needs at least one bin") };
+    let mut bins = [0; N];
+    for color in colors {
+        let lstar = lightness_to_lstar(CIELUV::from(*color).l as Real) as f32;
+        let bin = (lstar / 100.0 * N as f32) as usize;
+        bins[bin.min(N - 1)] += 1;
+    }
+    bins
+}
+
+/// Overlay temporally decaying sparkles of `color` on a buffer of colors, one frame at a time.
+///
+/// `sparkles` holds the intensity of the sparkle at each LED between frames,
+/// and must be as long as `colors`. Render the base colors, then call this function once per frame.
+///
+/// * Existing sparkles lose `decay` of their intensity each frame, from 0.0 (never fade) to 1.0 (fade instantly),
+/// * new sparkles ignite at full intensity with a probability of `density` per LED and frame,
+/// * `random` must return uniformly distributed values in the range `0.0..1.0`.
+///
+/// Sparkles are composited onto the base colors in linear light, see [`RGB::tint`].
+pub fn sparkle(
+    colors: &mut [RGB],
+    sparkles: &mut [f32],
+    color: &RGB,
+    density: f32,
+    decay: f32,
+    mut random: impl FnMut() -> f32,
+) {
+    assert_eq!(
+        colors.len(),
+        sparkles.len(),
+        "sparkles must be as long as colors"
+    );
+
+    for (base, intensity) in colors.iter_mut().zip(sparkles.iter_mut()) {
+        *intensity *= 1.0 - decay;
+        if random() < density {
+            *intensity = 1.0;
+        }
+        *base = base.tint(color, *intensity);
+    }
+}
+
+/// Output levels for a fixture with `N` channels, such as RGB+Amber+UV, each nominally within `0.0..1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelVec<const N: usize>(pub [f32; N]);
+
+impl<const N: usize> Default for ChannelVec<N> {
+    fn default() -> Self {
+        Self([0.0; N])
+    }
+}
+
+impl<const N: usize> From<[f32; N]> for ChannelVec<N> {
+    fn from(channels: [f32; N]) -> Self {
+        Self(channels)
+    }
+}
+
+impl<const N: usize> ChannelVec<N> {
+    /// Duty cycles that mix `emitters` into `target`, see [`solve_emitters`].
+    ///
+    /// Returns the duty cycles along with the remaining distance in XYZ between `target` and the mix.
+    pub fn solve(emitters: &[XYZ; N], target: &XYZ) -> (Self, f32) {
+        let mut duty = Self::default();
+        let error = solve_emitters(emitters, target, &mut duty.0);
+        (duty, error)
+    }
+
+    /// Interpolate every channel towards `end`, based on a parameter `t` (0.0 to 1.0).
+    pub fn lerp(&self, end: &Self, t: f32) -> Self {
+        Self(core::array::from_fn(|i| lerp(self.0[i], end.0[i], t)))
+    }
+
+    /// Multiply every channel by `factor`.
+    pub fn scale(&self, factor: f32) -> Self {
+        Self(self.0.map(|c| c * factor))
+    }
+
+    /// Limit every channel to the range `min..max`.
+    pub fn clamp(&self, min: f32, max: f32) -> Self {
+        Self(self.0.map(|c| c.clamp(min, max)))
+    }
+}
+
+/// Compute duty cycles for a fixture with an arbitrary set of emitters, such as RGBA or RGB+Lime,
+/// so that the mix of their light matches `target`.
+///
+/// `emitters` holds the measured XYZ of each emitter at full output. After the call,
+/// `duty` holds the duty cycle of each emitter, in the range of 0.0..1.0.
+///
+/// The duty cycles are found by bounded least squares using coordinate descent,
+/// which also covers targets outside of what the emitters can produce.
+/// Returns the remaining distance in XYZ between `target` and the mix.
+pub fn solve_emitters(emitters: &[XYZ], target: &XYZ, duty: &mut [f32]) -> f32 {
+    assert_eq!(
+        emitters.len(),
+        duty.len(),
+        "duty must be as long as emitters"
+    );
+
+    const MAX_SWEEPS: usize = 1000;
+    const TOLERANCE: f32 = 1e-7;
+
+    duty.fill(0.0);
+    let mut residual = [target.x, target.y, target.z];
+
+    for _ in 0..MAX_SWEEPS {
+        let mut largest_change: f32 = 0.0;
+
+        for (emitter, d) in emitters.iter().zip(duty.iter_mut()) {
+            let column = [emitter.x, emitter.y, emitter.z];
+            let norm = column.iter().map(|c| c * c).sum::<f32>();
+            if norm <= 0.0 {
+                continue;
+            }
+
+            let dot = column.iter().zip(residual).map(|(c, r)| c * r).sum::<f32>();
+            let updated = (*d + dot / norm).clamp(0.0, 1.0);
+            let change = updated - *d;
+            for (r, c) in residual.iter_mut().zip(column) {
+                *r -= change * c;
+            }
+            *d = updated;
+            largest_change = largest_change.max(change.abs());
+        }
+
+        if largest_change < TOLERANCE {
+            break;
+        }
+    }
+
+    residual.iter().map(|r| r * r).sum::<f32>().sqrt()
+}
+
+/// Estimate the fraction of a target gamut that a set of emitters can reproduce, from 0.0..1.0.
+///
+/// The target gamut is given by the XYZ of its red, green and blue primaries at full intensity,
+/// e.g. `[RGB::RED, RGB::GREEN, RGB::BLUE].map(XYZ::from)` for sRGB. Its color cube is sampled
+/// on a linear grid with `steps` points along each axis, and every sample is matched with
+/// [`solve_emitters`]. Samples the emitters can mix within a small tolerance count as covered.
+/// The emitters may also be the colors of a palette, to estimate the part of the target gamut
+/// their mixtures cover. All XYZ values must be scaled so that sRGB white has a luminance
+/// of Y = 1.0, as produced by `XYZ::from(RGB::WHITE)`.
+pub fn gamut_coverage<const N: usize>(emitters: &[XYZ; N], target: &[XYZ; 3], steps: usize) -> f32 {
+    const TOLERANCE: f32 = 0.001;
+
+    let steps = steps.max(2);
+    let scale = (steps - 1) as f32;
+    let [red, green, blue] = target;
+    let mut covered = 0;
+
+    for r in 0..steps {
+        for g in 0..steps {
+            for b in 0..steps {
+                let [r, g, b] = [r, g, b].map(|c| c as f32 / scale);
+                let sample = XYZ {
+                    x: r * red.x + g * green.x + b * blue.x,
+                    y: r * red.y + g * green.y + b * blue.y,
+                    z: r * red.z + g * green.z + b * blue.z,
+                };
+                if ChannelVec::solve(emitters, &sample).1 < TOLERANCE {
+                    covered += 1;
+                }
+            }
+        }
+    }
+
+    covered as f32 / steps.pow(3) as f32
+}
+
+/// Buffer of colors that tracks which entries changed since they were last converted,
+/// so that mostly static scenes only convert the few entries that change.
+///
+/// All entries start out changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirtyBuffer<const N: usize> {
+    colors: [CIELUV; N],
+    dirty: [bool; N],
+}
+
+impl<const N: usize> Default for DirtyBuffer<N> {
+    fn default() -> Self {
+        Self {
+            colors: [CIELUV::default(); N],
+            dirty: [true; N],
+        }
+    }
+}
+
+impl<const N: usize> DirtyBuffer<N> {
+    pub fn colors(&self) -> &[CIELUV; N] {
+        &self.colors
+    }
+
+    /// Replace the color at `index`, marking it as changed if it differs from the current color.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, color: CIELUV) {
+        if self.colors[index] != color {
+            self.colors[index] = color;
+            self.dirty[index] = true;
+        }
+    }
+
+    /// Number of entries changed since the last conversion.
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.iter().filter(|d| **d).count()
+    }
+
+    /// Convert the changed entries into `out`, leaving the other entries as they are,
+    /// and mark all entries as unchanged. Returns the number of converted entries.
+    pub fn convert_dirty<T: From<CIELUV>>(&mut self, out: &mut [T; N]) -> usize {
+        let mut count = 0;
+        for ((color, dirty), out) in self.colors.iter().zip(&mut self.dirty).zip(out) {
+            if *dirty {
+                *out = T::from(*color);
+                *dirty = false;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Order in which the LEDs of a matrix are wired, mapping a position to an index in the strip.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixLayout {
+    /// Rows from top to bottom, each from left to right.
+    #[default]
+    RowMajor,
+    /// Columns from left to right, each from top to bottom.
+    ColumnMajor,
+    /// Rows from top to bottom, alternating between left to right and right to left.
+    Serpentine,
+    /// Columns from left to right, alternating between top to bottom and bottom to top.
+    ColumnSerpentine,
+}
+
+impl MatrixLayout {
+    /// Index in the strip of the LED at column `x` and row `y`, in a matrix of `width` by `height` LEDs.
+    pub fn index(&self, x: usize, y: usize, width: usize, height: usize) -> usize {
+        match self {
+            MatrixLayout::RowMajor => y * width + x,
+            MatrixLayout::ColumnMajor => x * height + y,
+            MatrixLayout::Serpentine if y % 2 == 1 => y * width + (width - 1 - x),
+            MatrixLayout::Serpentine => y * width + x,
+            MatrixLayout::ColumnSerpentine if x % 2 == 1 => x * height + (height - 1 - y),
+            MatrixLayout::ColumnSerpentine => x * height + y,
+        }
+    }
+}
+
+/// A display made of identical matrix panels chained into one strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TiledLayout {
+    /// Width of each panel, in LEDs.
+    pub panel_width: usize,
+    /// Height of each panel, in LEDs.
+    pub panel_height: usize,
+    /// Wiring of the LEDs within each panel.
+    pub panel_layout: MatrixLayout,
+    /// Number of panels across.
+    pub panels_x: usize,
+    /// Number of panels down.
+    pub panels_y: usize,
+    /// Order in which the panels are chained.
+    pub tile_layout: MatrixLayout,
+}
+
+impl TiledLayout {
+    /// Index in the strip of the LED at column `x` and row `y` of the whole display.
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        let panel = self.tile_layout.index(
+            x / self.panel_width,
+            y / self.panel_height,
+            self.panels_x,
+            self.panels_y,
+        );
+        let offset = self.panel_layout.index(
+            x % self.panel_width,
+            y % self.panel_height,
+            self.panel_width,
+            self.panel_height,
+        );
+        panel * self.panel_width * self.panel_height + offset
+    }
+
+    /// Width of the whole display, in LEDs.
+    pub fn width(&self) -> usize {
+        self.panel_width * self.panels_x
+    }
+
+    /// Height of the whole display, in LEDs.
+    pub fn height(&self) -> usize {
+        self.panel_height * self.panels_y
+    }
+}
+
+/// Compare two frames of colors, and iterate over the entries that changed perceptibly.
+///
+/// Yields the index and new color of every entry in `next` whose [`CIELUV::delta_e`]
+/// from the same entry in `previous` exceeds `threshold`, so that only those need to be sent to the LEDs.
+///
+/// Panics if the frames are not of equal length.
+pub fn frame_delta<'a>(
+    previous: &'a [CIELUV],
+    next: &'a [CIELUV],
+    threshold: f32,
+) -> impl Iterator<Item = (usize, CIELUV)> + 'a {
+    assert_eq!(previous.len(), next.len(), "frames must be of equal length");
+
+    previous
+        .iter()
+        .zip(next)
+        .enumerate()
+        .filter(move |(_, (a, b))| a.delta_e(b) > threshold)
+        .map(|(i, (_, b))| (i, *b))
+}
+
+/// Blend two buffers through a luminance mask, taking shadows from `a` and highlights from `b`.
+///
+/// The weight of `b` in each entry is the lightness of the entry in `a` relative to white,
+/// so black entries keep `a` and white entries become `b`. The entries are mixed in linear light,
+/// see [`RGB::tint`].
+///
+/// Panics if the buffers and `out` are not of equal length.
+pub fn blend_by_luminance(a: &[RGB], b: &[RGB], out: &mut [RGB]) {
+    assert_eq!(a.len(), b.len(), "buffers must be of equal length");
+    assert_eq!(a.len(), out.len(), "output must be as long as the buffers");
+
+    for ((a, b), out) in a.iter().zip(b).zip(out) {
+        let mask = CIELUV::from(*a).relative_lightness();
+        *out = a.tint(b, mask);
+    }
+}
+
+/// Apply a gradient over a buffer through a per-LED alpha `mask` from 0.0 (unchanged) to 1.0
+/// (fully the gradient), for effects that only cover part of a strip.
+///
+/// The gradient is sampled at evenly spaced positions from 0.0 to 1.0 along the buffer,
+/// and composited over the existing colors in linear light, see [`RGB::tint`].
+///
+/// Panics if `mask` and `buffer` are not of equal length.
+pub fn apply_masked(gradient: impl Fn(f32) -> CIELUV, mask: &[f32], buffer: &mut [RGB]) {
+    assert_eq!(
+        mask.len(),
+        buffer.len(),
+        "mask must be as long as the buffer"
+    );
+
+    let last = buffer.len().saturating_sub(1).max(1);
+    for (i, (alpha, color)) in mask.iter().zip(buffer).enumerate() {
+        let alpha = alpha.clamp(0.0, 1.0);
+        if alpha > 0.0 {
+            let t = i as f32 / last as f32;
+            *color = color.tint(&gradient(t).into(), alpha);
+        }
+    }
+}
+
+/// Scale a buffer of colors down to the length of `dst`, such as from high resolution
+/// content to a coarse LED matrix.
+///
+/// Every entry in `dst` is the average of a group of adjacent entries in `src`.
+/// Colors are averaged in linear light, so that fine detail does not turn darker
+/// as it does when averaging gamma-encoded sRGB values.
+///
+/// Panics if `dst` is longer than `src`.
+pub fn downsample(src: &[RGB], dst: &mut [RGB]) {
+    assert!(dst.len() <= src.len(), "dst must not be longer than src");
+
+    let len = dst.len();
+    for (i, out) in dst.iter_mut().enumerate() {
+        let group = &src[i * src.len() / len..(i + 1) * src.len() / len];
+        let mut sum: [Real; 3] = [0.0; 3];
+        for color in group {
+            sum[0] += srgb_to_linear(color.r as Real);
+            sum[1] += srgb_to_linear(color.g as Real);
+            sum[2] += srgb_to_linear(color.b as Real);
+        }
+        let [r, g, b] = sum.map(|c| linear_to_srgb(c / group.len() as Real) as f32);
+        *out = RGB { r, g, b };
+    }
+}
+
+/// Phase of an animation looping every `period`, from 0.0..1.0, after `elapsed` time.
+///
+/// The phase is derived from whole nanoseconds rather than accumulated in floating point,
+/// so it does not drift however long the animation runs. Use it as `t` in [`CIELUV::interpolate`].
+pub fn phase(elapsed: Duration, period: Duration) -> f32 {
+    let period = period.as_nanos();
+    if period == 0 {
+        return 0.0;
+    }
+    // Divide in double precision and stay below 1.0, so that the phase wraps to 0.0
+    // only at the end of the period, even where f32 would round up.
+    let phase = (elapsed.as_nanos() % period) as f64 / period as f64;
+    (phase as f32).min(1.0 - f32::EPSILON)
+}
+
+/// Like [`phase`], but runs from 0.0 to 1.0 during the first half of `period` and back to 0.0 during the second,
+/// for animations that go back and forth without a jump.
+pub fn ping_pong_phase(elapsed: Duration, period: Duration) -> f32 {
+    1.0 - (2.0 * phase(elapsed, period) - 1.0).abs()
+}
+
+/// Run the conversions on the [`reference`](mod@reference) test vectors, and return whether
+/// all results are within tolerance.
+///
+/// Call this at boot to catch floating point settings or feature combinations
+/// that are broken on the target hardware, instead of rendering wrong colors.
+pub fn self_check() -> bool {
+    let close = |a: [f32; 3], b: [f32; 3], tolerance: f32| {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < tolerance)
+    };
+
+    reference::SRGB.iter().all(|color| {
+        let xyz = XYZ::from(color.rgb);
+        let cieluv = CIELUV::from(color.xyz);
+        let rgb = RGB::from(cieluv);
+        close(
+            [xyz.x, xyz.y, xyz.z],
+            [color.xyz.x, color.xyz.y, color.xyz.z],
+            reference::TOLERANCE,
+        ) && close(
+            [cieluv.l, cieluv.u, cieluv.v],
+            [color.cieluv.l, color.cieluv.u, color.cieluv.v],
+            reference::TOLERANCE,
+        ) && close(
+            [rgb.r, rgb.g, rgb.b],
+            [color.rgb.r, color.rgb.g, color.rgb.b],
+            reference::RGB_TOLERANCE,
+        )
+    })
+}
+
+/// Move `current` towards `target` by at most `max_delta_e` in [`CIELUV::delta_e`],
+/// preventing jarring jumps when the target changes abruptly.
+///
+/// Call once per frame with the previous result as `current`, so that colors change
+/// at a capped perceptual rate.
+pub fn slew(current: &CIELUV, target: &CIELUV, max_delta_e: f32) -> CIELUV {
+    let distance = current.delta_e(target);
+    if distance <= max_delta_e {
+        return *target;
+    }
+    current.interpolate(target, max_delta_e.max(0.0) / distance)
+}
+
+/// Low-pass filter for noisy color inputs, such as colors derived from a microphone or camera,
+/// that smooths them before display to avoid flicker.
+///
+/// Colors are smoothed exponentially in the CIELUV color space, so that the output
+/// moves towards the input at a perceptually even rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorFilter {
+    time_constant: Duration,
+    value: Option<CIELUV>,
+}
+
+impl ColorFilter {
+    /// Create a filter where the output covers about 63% of the distance
+    /// to a new input within `time_constant`.
+    pub const fn new(time_constant: Duration) -> Self {
+        Self {
+            time_constant,
+            value: None,
         }
     }
+
+    /// Feed the filter with `input`, `elapsed` time after the previous update,
+    /// and return the smoothed color.
+    ///
+    /// The first update after creation or [`ColorFilter::reset`] returns `input` as is.
+    pub fn update(&mut self, input: CIELUV, elapsed: Duration) -> CIELUV {
+        let value = match self.value {
+            Some(value) if !self.time_constant.is_zero() => {
+                let ratio = elapsed.as_secs_f32() / self.time_constant.as_secs_f32();
+                value.interpolate(&input, 1.0 - (-ratio).exp())
+            }
+            _ => input,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    /// The current output of the filter, if it has been updated.
+    pub fn value(&self) -> Option<CIELUV> {
+        self.value
+    }
+
+    /// Forget the current output, so that the next update starts over from its input.
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// Shortest angular distance between two hues, in degrees.
+#[inline]
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let distance = (a - b).abs() % 360.0;
+    distance.min(360.0 - distance)
 }
 
 /// Helper function to perform linear interpolation
 #[inline]
 pub fn lerp(start: f32, end: f32, t: f32) -> f32 {
-    start + t * (end - start)
+    let (start, end) = (start as Real, end as Real);
+    (start + t as Real * (end - start)) as f32
 }
 
-const GAMMA: f32 = 2.4;
+const GAMMA: Real = 2.4;
 
 /// Convert sRGB to linear RGB (inverse sRGB companding)
 /// Verified here: http://www.brucelindbloom.com/index.html?Eqn_RGB_to_XYZ.html
 #[inline]
-fn srgb_to_linear(c: f32) -> f32 {
+fn srgb_to_linear(c: Real) -> Real {
     if c <= 0.04045 {
         c / 12.92
     } else {
@@ -450,10 +4430,25 @@ fn srgb_to_linear(c: f32) -> f32 {
 /// Convert linear RGB to sRGB
 /// Verified here: http://www.brucelindbloom.com/index.html?Eqn_XYZ_to_RGB.html
 #[inline]
-fn linear_to_srgb(c: f32) -> f32 {
+fn linear_to_srgb(c: Real) -> Real {
     if c <= 0.0031308 {
         12.92 * c
     } else {
         1.055 * c.powf(1.0 / GAMMA) - 0.055
     }
-}
\ No newline at end of file
+}
+
+/// Adobe RGB (1998) uses a pure gamma curve of 563/256, approximately 2.2.
+const ADOBE_GAMMA: Real = 563.0 / 256.0;
+
+/// Convert Adobe RGB to linear RGB
+#[inline]
+fn adobe_to_linear(c: Real) -> Real {
+    c.max(0.0).powf(ADOBE_GAMMA)
+}
+
+/// Convert linear RGB to Adobe RGB
+#[inline]
+fn linear_to_adobe(c: Real) -> Real {
+    c.max(0.0).powf(1.0 / ADOBE_GAMMA)
+}
@@ -0,0 +1,97 @@
+//! Conversion test vectors.
+//!
+//! XYZ values are the published sRGB/D65 values from Bruce Lindbloom's
+//! [color calculator](http://www.brucelindbloom.com/ColorCalculator.html),
+//! scaled so that the white point has `y = 1.0`.
+//! CIELUV values follow from those, using this crate's lightness scale
+//! where white has `l ≈ 8.99` (see [`CIELUV`]).
+//! The XYZ to RGB direction uses the higher precision sYCC matrix,
+//! and is only expected to match the RGB values within [`RGB_TOLERANCE`].
+//!
+//! Use these to validate custom pipelines and color spaces against the same
+//! data that the crate is tested with.
+
+use crate::{CIELUV, RGB, XYZ};
+
+/// A single color expressed in each of the reference color spaces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceColor {
+    pub rgb: RGB,
+    pub xyz: XYZ,
+    pub cieluv: CIELUV,
+}
+
+/// Largest absolute difference per component expected between a conversion and the reference values.
+pub const TOLERANCE: f32 = 0.0005;
+
+/// Largest absolute difference per component expected when converting back to RGB.
+pub const RGB_TOLERANCE: f32 = 0.005;
+
+/// Primaries, secondaries, white, black and middle gray.
+pub const SRGB: [ReferenceColor; 9] = [
+    reference(
+        (1.0, 1.0, 1.0),
+        (0.950470, 1.000000, 1.088830),
+        (8.9914, 0.0, 0.0),
+    ),
+    reference((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+    reference(
+        (1.0, 0.0, 0.0),
+        (0.412456, 0.212673, 0.019334),
+        (1.9211, 6.3150, 1.3624),
+    ),
+    reference(
+        (0.0, 1.0, 0.0),
+        (0.357576, 0.715152, 0.119192),
+        (6.4599, -6.1170, 7.9078),
+    ),
+    reference(
+        (0.0, 0.0, 1.0),
+        (0.180437, 0.072175, 0.950304),
+        (0.6520, -0.1899, -2.6311),
+    ),
+    reference(
+        (0.0, 1.0, 1.0),
+        (0.538014, 0.787327, 1.069496),
+        (7.1119, -5.5012, -1.1868),
+    ),
+    reference(
+        (1.0, 0.0, 1.0),
+        (0.592894, 0.284848, 0.969638),
+        (2.5730, 3.5859, -4.6357),
+    ),
+    reference(
+        (1.0, 1.0, 0.0),
+        (0.770033, 0.927825, 0.138526),
+        (8.3751, 0.6644, 9.2069),
+    ),
+    reference(
+        (0.5, 0.5, 0.5),
+        (0.203440, 0.214041, 0.233054),
+        (1.9334, 0.0, 0.0),
+    ),
+];
+
+const fn reference(
+    rgb: (f32, f32, f32),
+    xyz: (f32, f32, f32),
+    cieluv: (f32, f32, f32),
+) -> ReferenceColor {
+    ReferenceColor {
+        rgb: RGB {
+            r: rgb.0,
+            g: rgb.1,
+            b: rgb.2,
+        },
+        xyz: XYZ {
+            x: xyz.0,
+            y: xyz.1,
+            z: xyz.2,
+        },
+        cieluv: CIELUV {
+            l: cieluv.0,
+            u: cieluv.1,
+            v: cieluv.2,
+        },
+    }
+}
@@ -32,7 +32,7 @@ fn approximately_equal(actual: RGBW, expected: RGBW) {
 fn assert_clean_cieluv_conversion(rgb: RGB) {
     let expected = RGBW::from(rgb);
     let cieluv = CIELUV::from(rgb);
-    approximately_equal(cieluv.into(), expected.into());
+    approximately_equal(cieluv.into(), expected);
 }
 
 /// Red, green, blue, yellow and magenta convert cleanly to CIELUV and back.
@@ -83,9 +83,10 @@ fn print_gradient_as_rgbw(a: impl Into<CIELUV>, b: impl Into<CIELUV>, steps: usi
         let sat = cieluv.saturation();
         let c = cieluv.chroma();
         let hue = cieluv.hue();
+        let swatch = Swatch(cieluv.into());
         assert!(hue >= 0.0);
         assert!(hue <= 360.0);
-        println!("Gradient {i:1.03}: L*={l:1.03}, sat={sat:1.03}, chroma={c:1.03}, hue={hue:1.01} // {rgbw}");
+        println!("Gradient {i:1.03}: {swatch} L*={l:1.03}, sat={sat:1.03}, chroma={c:1.03}, hue={hue:1.01} // {rgbw}");
     }
 }
 
@@ -122,4 +123,1417 @@ fn test_rgbw_green_magenta() {
         b: 1.0,
     });
     print_gradient_as_rgbw(RGB::GREEN, magenta, 100);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_palette_hue_pairing() {
+    let start = [
+        CIELUV::from(RGB::RED),
+        CIELUV::from(RGB::GREEN),
+        CIELUV::from(RGB::BLUE),
+    ];
+    let end = [
+        CIELUV::from(RGB::BLUE),
+        CIELUV::from(RGB::RED),
+        CIELUV::from(RGB::GREEN),
+    ];
+
+    let mut pairs = [0; 3];
+    pair_palettes_by_hue(&start, &end, &mut pairs);
+    assert_eq!(pairs, [1, 2, 0]);
+
+    let mut out = [CIELUV::default(); 3];
+    interpolate_palettes(&start, &end, Some(&pairs), 1.0, &mut out);
+    assert_eq!(out, start);
+}
+
+#[test]
+fn test_response_curve() {
+    // A driver that needs twice the drive level for the same light output at the low end.
+    let curve = ResponseCurve::new(&[(0.0, 0.0), (0.5, 0.25), (1.0, 1.0)]);
+    assert_eq!(curve.drive(0.0), 0.0);
+    assert_eq!(curve.drive(0.25), 0.5);
+    assert_eq!(curve.drive(0.625), 0.75);
+    assert_eq!(curve.drive(1.5), 1.0);
+    assert_eq!(ResponseCurve::default().drive(0.3), 0.3);
+
+    let response = DeviceResponse {
+        r: curve,
+        ..Default::default()
+    };
+    let gray = RGB {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+    };
+    let drive = response.drive_levels(&gray);
+    assert_eq!([drive.r, drive.g, drive.b].map(round), [0.43, 0.21, 0.21]);
+    assert_eq!(response.drive_levels(&RGB::WHITE), RGB::WHITE);
+}
+
+#[test]
+fn test_with_lightness_stays_in_gamut() {
+    let red = CIELUV::from(RGB::RED);
+    let light_red = red.with_lightness(8.0);
+    assert!(light_red.in_gamut());
+    assert_eq!(light_red.l, 8.0);
+    assert!((light_red.hue() - red.hue()).abs() < 0.01);
+    assert!(light_red.chroma() < red.chroma());
+
+    // Already at the gamut boundary, so the chroma is kept.
+    assert!((red.with_lightness(red.l).chroma() - red.chroma()).abs() < 0.001);
+}
+
+#[test]
+fn test_max_chroma_matches_gamut_boundary() {
+    for rgb in [RGB::RED, RGB::GREEN, RGB::BLUE] {
+        let cieluv = CIELUV::from(rgb);
+        let max = max_chroma(cieluv.l, cieluv.hue());
+        assert!(
+            (max - cieluv.chroma()).abs() < 0.01,
+            "{cieluv}: max chroma {max}"
+        );
+    }
+    assert_eq!(max_chroma(0.0, 120.0), 0.0);
+}
+
+#[test]
+fn test_boost_saturation_caps_at_gamut() {
+    let pink = CIELUV::from(RGB {
+        r: 0.8,
+        g: 0.5,
+        b: 0.5,
+    });
+    let vivid = pink.boost_saturation(10.0);
+    assert!(vivid.chroma() > pink.chroma());
+    assert!((vivid.chroma() - max_chroma(pink.l, pink.hue())).abs() < 0.001);
+    assert!((vivid.hue() - pink.hue()).abs() < 0.01);
+    assert_eq!(vivid.l, pink.l);
+}
+
+#[test]
+fn test_vibrance_favors_muted_colors() {
+    let muted = CIELUV::from(RGB {
+        r: 0.6,
+        g: 0.5,
+        b: 0.5,
+    });
+    let vivid = CIELUV::from(RGB {
+        r: 0.9,
+        g: 0.2,
+        b: 0.2,
+    });
+    let muted_gain = muted.vibrance(0.5).chroma() / muted.chroma();
+    let vivid_gain = vivid.vibrance(0.5).chroma() / vivid.chroma();
+    assert!(muted_gain > vivid_gain);
+    assert_eq!(muted.vibrance(0.0), muted);
+}
+
+#[test]
+fn test_duotone_and_tritone() {
+    let shadows = CIELUV::from(RGB::BLUE);
+    let midtones = CIELUV::from(RGB::RED);
+    let highlights = CIELUV::from(RGB::GREEN);
+
+    let black = CIELUV::from(RGB::BLACK);
+    let white = CIELUV::from(RGB::WHITE);
+    assert_eq!(black.duotone(&shadows, &highlights), shadows);
+    approximately_equal(
+        white.duotone(&shadows, &highlights).into(),
+        highlights.into(),
+    );
+    assert_eq!(black.tritone(&shadows, &midtones, &highlights), shadows);
+    approximately_equal(
+        white.tritone(&shadows, &midtones, &highlights).into(),
+        highlights.into(),
+    );
+}
+
+#[test]
+fn test_posterize() {
+    let hcl = HCL::from(RGB {
+        r: 0.7,
+        g: 0.3,
+        b: 0.2,
+    });
+    let poster = hcl.posterize(4);
+    assert_eq!(poster.h % 90.0, 0.0);
+    assert_eq!(poster.posterize(4), poster);
+    assert_eq!(HCL::from(RGB::BLACK).posterize(4).l, 0.0);
+
+    // Levels are evenly spaced in CIE L*, not in luminance.
+    let levels = [0.1, 0.3, 0.5, 0.7, 0.9].map(|v| {
+        let poster = HCL::from(RGB { r: v, g: v, b: v }).posterize(5);
+        round(lstar(XYZ::from(CIELUV::from(poster)).y as Real) as f32)
+    });
+    assert_eq!(levels, [0.0, 25.0, 50.0, 75.0, 100.0]);
+}
+
+#[test]
+fn test_tint_and_sepia() {
+    let gray = RGB {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+    };
+    approximately_equal(gray.tint(&RGB::RED, 0.0).into(), gray.into());
+    approximately_equal(RGB::BLACK.tint(&RGB::WHITE, 1.0).into(), RGB::WHITE.into());
+
+    // Mixing in linear light is brighter than averaging sRGB values.
+    assert!(RGB::BLACK.tint(&RGB::WHITE, 0.5).r > 0.5);
+
+    let sepia = HCL::from(gray.sepia(1.0));
+    assert!((sepia.h - HCL::from(RGB::SEPIA).h).abs() < 1.0);
+    assert!((sepia.l - HCL::from(gray).l).abs() < 0.01);
+}
+
+#[test]
+fn test_histograms() {
+    let colors = [RGB::RED, RGB::GREEN, RGB::BLUE, RGB::BLACK, RGB::WHITE];
+
+    let hues: [u32; 4] = hue_histogram(&colors);
+    assert_eq!(hues.iter().sum::<u32>(), 3);
+
+    // Blue and black are darker than L* 50, red, green and white lighter.
+    let lightness: [u32; 2] = lightness_histogram(&colors);
+    assert_eq!(lightness, [2, 3]);
+}
+
+#[test]
+fn test_sparkle_decays() {
+    let mut colors = [RGB::BLACK; 4];
+    let mut sparkles = [0.0; 4];
+
+    sparkle(&mut colors, &mut sparkles, &RGB::WHITE, 0.5, 0.5, || 0.0);
+    assert_eq!(sparkles, [1.0; 4]);
+    approximately_equal(colors[0].into(), RGB::WHITE.into());
+
+    let mut colors = [RGB::BLACK; 4];
+    sparkle(&mut colors, &mut sparkles, &RGB::WHITE, 0.5, 0.5, || 1.0);
+    assert_eq!(sparkles, [0.5; 4]);
+    assert!(colors[0].r > 0.0 && colors[0].r < 1.0);
+}
+
+#[test]
+fn test_rotate_hue_keeps_lightness() {
+    let blue = HCL::from(RGB::BLUE);
+    for degrees in [-90.0, 45.0, 180.0, 400.0] {
+        let rotated = blue.rotate_hue(degrees);
+        assert!((0.0..360.0).contains(&rotated.h));
+        let lightness = CIELUV::from(RGB::from(rotated)).l;
+        assert!((lightness - blue.l).abs() < 0.01, "rotated by {degrees}");
+    }
+}
+
+#[test]
+fn test_solve_emitters_with_srgb_primaries() {
+    let emitters = [
+        XYZ::from(RGB::RED),
+        XYZ::from(RGB::GREEN),
+        XYZ::from(RGB::BLUE),
+    ];
+    let target = XYZ::from(RGB {
+        r: 0.5,
+        g: 0.2,
+        b: 0.8,
+    });
+
+    let mut duty = [0.0; 3];
+    let error = solve_emitters(&emitters, &target, &mut duty);
+    assert!(error < 0.001, "error {error}");
+
+    let mix = RGB::from(XYZ {
+        x: emitters.iter().zip(duty).map(|(e, d)| e.x * d).sum(),
+        y: emitters.iter().zip(duty).map(|(e, d)| e.y * d).sum(),
+        z: emitters.iter().zip(duty).map(|(e, d)| e.z * d).sum(),
+    });
+    approximately_equal(mix.into(), RGB::from(target).into());
+}
+
+#[test]
+fn test_gamut_coverage() {
+    let srgb = [RGB::RED, RGB::GREEN, RGB::BLUE].map(XYZ::from);
+    assert_eq!(gamut_coverage(&srgb, &srgb, 5), 1.0);
+
+    let without_blue = [XYZ::from(RGB::RED), XYZ::from(RGB::GREEN)];
+    let coverage = gamut_coverage(&without_blue, &srgb, 5);
+    assert!(coverage > 0.0 && coverage < 0.5, "coverage {coverage}");
+
+    // Custom targets, with primaries moved towards or away from white.
+    let white = XYZ::from(RGB::WHITE);
+    let towards_white = |p: XYZ, t: f32| XYZ {
+        x: p.x + t * (white.x / 3.0 - p.x),
+        y: p.y + t * (white.y / 3.0 - p.y),
+        z: p.z + t * (white.z / 3.0 - p.z),
+    };
+    let narrow = srgb.map(|p| towards_white(p, 0.2));
+    assert_eq!(gamut_coverage(&srgb, &narrow, 5), 1.0);
+    let wide = srgb.map(|p| towards_white(p, -0.2));
+    assert!(gamut_coverage(&srgb, &wide, 5) < 1.0);
+}
+
+#[test]
+fn test_compensate_dimming() {
+    let color = CIELUV::from(RGB {
+        r: 0.3,
+        g: 0.2,
+        b: 0.2,
+    });
+    assert_eq!(color.compensate_dimming(0.0), color);
+
+    let compensated = color.compensate_dimming(1.0);
+    assert!(compensated.chroma() > color.chroma());
+    assert_eq!(compensated.l, color.l);
+}
+
+#[test]
+fn test_frame_delta() {
+    let previous = [CIELUV::from(RGB::RED), CIELUV::from(RGB::GREEN)];
+    let mut next = previous;
+    next[1].l += 0.001;
+    assert_eq!(frame_delta(&previous, &next, 0.01).count(), 0);
+
+    next[1] = CIELUV::from(RGB::BLUE);
+    let changed: std::vec::Vec<_> = frame_delta(&previous, &next, 0.01).collect();
+    assert_eq!(changed, [(1, next[1])]);
+}
+
+#[test]
+fn test_tone_map() {
+    let white = XYZ::from(RGB::WHITE);
+    let absolute = |nits: f32| XYZ {
+        x: white.x * nits,
+        y: white.y * nits,
+        z: white.z * nits,
+    };
+
+    assert!((absolute(500.0).tone_map(1000.0).y - 0.5).abs() < 0.001);
+    let highlight = absolute(4000.0).tone_map(1000.0);
+    assert!(highlight.y > 0.75 && highlight.y < 1.0);
+    assert!(absolute(2000.0).tone_map(1000.0).y < highlight.y);
+    assert!((highlight.x / highlight.y - white.x / white.y).abs() < 0.001);
+}
+
+#[test]
+fn test_blend_by_luminance() {
+    let a = [RGB::BLACK, RGB::WHITE];
+    let b = [RGB::RED, RGB::BLUE];
+    let mut out = [RGB::default(); 2];
+    blend_by_luminance(&a, &b, &mut out);
+    approximately_equal(out[0].into(), RGB::BLACK.into());
+    approximately_equal(out[1].into(), RGB::BLUE.into());
+}
+
+#[test]
+fn test_swatch() {
+    let swatch = Swatch(RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0,
+    });
+    assert_eq!(
+        std::format!("{swatch}"),
+        "\x1b[48;2;255;128;0m   \x1b[0m #ff8000"
+    );
+}
+
+#[test]
+fn test_c_array() {
+    let start = CIELUV::from(RGB::RED);
+    let end = CIELUV::from(RGB::BLUE);
+    let colors = [0.0, 1.0].map(|t| RGB8::from(RGB::from(start.interpolate(&end, t))));
+    let array = CArray::new("gradient", &colors).unwrap();
+    assert_eq!(
+        std::format!("{array}"),
+        "const uint8_t gradient[6] = {\n    0xff, 0x00, 0x00,\n    0x00, 0x00, 0xff\n};"
+    );
+    assert_eq!(CArray::new("empty", &[]), None);
+}
+
+#[test]
+fn test_phase() {
+    let period = Duration::from_secs(4);
+    assert_eq!(phase(Duration::from_secs(1), period), 0.25);
+    assert_eq!(phase(Duration::from_secs(4 * 1_000_000 + 3), period), 0.75);
+    assert_eq!(phase(Duration::from_secs(1), Duration::ZERO), 0.0);
+    // Just before the end of the period, the phase must not round up to 1.0.
+    let end = phase(Duration::from_nanos(9_999_999_999), Duration::from_secs(10));
+    assert!(end < 1.0, "{end}");
+
+    assert_eq!(ping_pong_phase(Duration::from_secs(1), period), 0.5);
+    assert_eq!(ping_pong_phase(Duration::from_secs(2), period), 1.0);
+    assert_eq!(ping_pong_phase(Duration::from_secs(3), period), 0.5);
+}
+
+#[test]
+fn test_color_correction() {
+    approximately_equal(
+        ColorCorrection::NONE.apply(&RGB::SEPIA).into(),
+        RGB::SEPIA.into(),
+    );
+
+    let white = ColorCorrection::TYPICAL_LED_STRIP.apply(&RGB::WHITE);
+    assert_eq!(round(white.r), 1.0);
+    // The factors scale linear light, not the sRGB values.
+    assert_eq!(round(white.g), 0.85);
+    assert_eq!(
+        ColorCorrection::from_u8(255, 176, 240),
+        ColorCorrection::TYPICAL_LED_STRIP
+    );
+}
+
+#[test]
+fn test_hue_correction() {
+    // Boost reds, leave greens and blues unchanged.
+    let correction = HueCorrection::new(&[(0.0, 1.2), (120.0, 1.0), (270.0, 1.0)]);
+    assert_eq!(correction.gain(0.0), 1.2);
+    assert_eq!(correction.gain(60.0), 1.1);
+    assert_eq!(correction.gain(180.0), 1.0);
+    // Between the last and the first point, the gain wraps around the hue circle.
+    assert_eq!(round(correction.gain(315.0)), 1.1);
+    assert_eq!(round(correction.gain(-45.0)), 1.1);
+    assert_eq!(HueCorrection::default().gain(90.0), 1.0);
+
+    let dim_red = HCL::from(RGB {
+        r: 0.2,
+        g: 0.0,
+        b: 0.0,
+    });
+    let corrected = correction.apply(&dim_red);
+    assert!(corrected.l > dim_red.l * 1.15, "{corrected:?}");
+    assert_eq!((corrected.h, corrected.c), (dim_red.h, dim_red.c));
+    let blue = HCL::from(RGB::BLUE);
+    assert_eq!(correction.apply(&blue), blue);
+    let gray = HCL::from(RGB {
+        r: 0.2,
+        g: 0.2,
+        b: 0.2,
+    });
+    assert_eq!(correction.apply(&gray), gray);
+}
+
+#[test]
+fn test_fastled_rainbow_hues() {
+    let rgb8 = |h: u8, s: u8, v: u8| {
+        let RGB8 { r, g, b } = FastLedHsv { h, s, v }.into();
+        [r, g, b]
+    };
+    assert_eq!(rgb8(0, 255, 255), [255, 0, 0]);
+    assert_eq!(rgb8(64, 255, 255), [171, 170, 0]);
+    assert_eq!(rgb8(96, 255, 255), [0, 255, 0]);
+    assert_eq!(rgb8(160, 255, 255), [0, 0, 255]);
+    assert_eq!(rgb8(42, 0, 255), [255, 255, 255]);
+    assert_eq!(rgb8(42, 255, 0), [0, 0, 0]);
+}
+
+#[test]
+fn test_achromatic_hue() {
+    let gray = HCL::from(RGB {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+    });
+    assert!(gray.is_achromatic());
+    assert_eq!(gray.h, 0.0);
+    assert_eq!(gray.hue(), None);
+    assert_eq!(gray.hue_or(120.0), 120.0);
+
+    let red = HCL::from(RGB::RED);
+    assert_eq!(red.hue(), Some(red.h));
+    assert_eq!(red.hue_or(120.0), red.h);
+}
+
+#[test]
+fn test_interpolate_through_white() {
+    let red = CIELUV::from(RGB::RED);
+    let blue = CIELUV::from(RGB::BLUE);
+    let white = CIELUV::from(RGB::WHITE);
+
+    assert_eq!(red.interpolate_through(&white, &blue, 0.0), red);
+    assert_eq!(red.interpolate_through(&white, &blue, 0.5), white);
+    approximately_equal(
+        red.interpolate_through(&white, &blue, 1.0).into(),
+        blue.into(),
+    );
+    assert!(red.interpolate_through(&white, &blue, 0.25).saturation() < red.saturation());
+}
+
+#[test]
+fn test_round_trip_error() {
+    for rgb in [RGB::RED, RGB::GREEN, RGB::BLUE, RGB::WHITE, RGB::SEPIA] {
+        let error = round_trip_error(rgb.into());
+        assert_eq!(error.channels, [0, 0, 0], "{rgb}");
+        assert!(error.delta_e < 0.01, "{rgb}");
+    }
+}
+
+#[test]
+fn test_convert_between_any_spaces() {
+    assert_eq!(RGB::RED.convert::<CIELUV>(), CIELUV::from(RGB::RED));
+    assert_eq!(RGB::RED.convert::<HCL>(), HCL::from(RGB::RED));
+
+    let hcl = HCL::from(RGB::BLUE);
+    approximately_equal(hcl.convert::<RGB>().into(), RGB::BLUE.into());
+    approximately_equal(
+        hcl.convert::<XYZ>().convert::<RGB>().into(),
+        RGB::BLUE.into(),
+    );
+}
+
+#[test]
+fn test_hcl8_round_trip() {
+    for rgb in [RGB::RED, RGB::GREEN, RGB::BLUE, RGB::SEPIA, RGB::WHITE] {
+        let compact = HCL8::from(HCL::from(rgb));
+        let restored = CIELUV::from(HCL::from(compact));
+        // Hue steps of 1.4 degrees are most visible at the corners of the gamut.
+        assert!(restored.delta_e(&rgb.into()) < 0.25, "{rgb}");
+        assert_eq!(HCL8::from(HCL::from(compact)), compact);
+    }
+    assert_eq!(RGB::RED.convert::<HCL8>().c, 255);
+
+    // Lightness steps are even in CIE L*, including at the dark end.
+    let lstar_of = |l: u8| lstar(XYZ::from(HCL8 { h: 0, c: 0, l }).y as Real) as f32;
+    for l in 0..8 {
+        let step = lstar_of(l + 1) - lstar_of(l);
+        assert!((step - 100.0 / 255.0).abs() < 0.001, "{l}: {step}");
+    }
+}
+
+#[test]
+fn test_palette_ref_sampling() {
+    static PALETTE: [RGB8; 3] = [
+        RGB8 { r: 255, g: 0, b: 0 },
+        RGB8 { r: 0, g: 255, b: 0 },
+        RGB8 { r: 0, g: 0, b: 255 },
+    ];
+    let palette = PaletteRef::new(&PALETTE);
+
+    assert_eq!(palette.sample(0.0), CIELUV::from(RGB::RED));
+    assert_eq!(palette.sample(0.5), CIELUV::from(RGB::GREEN));
+    assert_eq!(palette.sample(1.0), CIELUV::from(RGB::BLUE));
+    let red_green = CIELUV::from(RGB::RED).interpolate(&RGB::GREEN.into(), 0.5);
+    assert!(palette.sample(0.25).delta_e(&red_green) < 0.001);
+
+    assert_eq!(PaletteRef::new(&[]).sample(0.5), CIELUV::default());
+}
+
+#[test]
+fn test_palette16_color_from_palette() {
+    let mut entries = [RGB8::default(); 16];
+    entries[0] = RGB8 { r: 255, g: 0, b: 0 };
+    entries[1] = RGB8 { r: 0, g: 0, b: 255 };
+    let palette = Palette16(entries);
+
+    assert_eq!(
+        palette.color_from_palette(0, 255, Blend::Linear),
+        entries[0]
+    );
+    assert_eq!(palette.color_from_palette(8, 255, Blend::None), entries[0]);
+    assert_eq!(
+        palette.color_from_palette(8, 255, Blend::Linear),
+        RGB8 {
+            r: 127,
+            g: 0,
+            b: 128
+        }
+    );
+    assert_eq!(
+        palette.color_from_palette(0, 127, Blend::Linear),
+        RGB8 { r: 128, g: 0, b: 0 }
+    );
+    assert_eq!(
+        palette.color_from_palette(16, 0, Blend::Linear),
+        RGB8::default()
+    );
+
+    let perceptual = palette.color_from_palette(8, 255, Blend::Perceptual);
+    assert!(perceptual.r > 0 && perceptual.b > 0);
+
+    let expanded = Palette256::from(palette);
+    assert_eq!(
+        expanded.color_from_palette(8, 255),
+        palette.color_from_palette(8, 255, Blend::Linear)
+    );
+}
+
+#[test]
+fn test_limit_lightness() {
+    let green = CIELUV::from(RGB::GREEN);
+    let limited = green.limit_lightness(50.0);
+    assert_eq!(round(lstar(XYZ::from(limited).y as Real) as f32), 50.0);
+    assert!((limited.hue() - green.hue()).abs() < 0.01);
+    assert!((limited.saturation() - green.saturation()).abs() < 0.001);
+
+    let blue = CIELUV::from(RGB::BLUE);
+    assert_eq!(blue.limit_lightness(50.0), blue);
+}
+
+#[test]
+fn test_compare_gradients() {
+    let red = CIELUV::from(RGB::RED);
+    let blue = CIELUV::from(RGB::BLUE);
+    let gradient = |t| red.interpolate(&blue, t);
+
+    let same = compare_gradients(gradient, gradient, 10);
+    assert_eq!(same.max_delta_e, 0.0);
+
+    static BAKED: [RGB8; 2] = [RGB8 { r: 255, g: 0, b: 0 }, RGB8 { r: 0, g: 0, b: 255 }];
+    let palette = PaletteRef::new(&BAKED);
+    let baked = compare_gradients(gradient, |t| palette.sample(t), 10);
+    assert!(baked.max_delta_e < 0.001);
+    assert!(baked.mean_delta_e <= baked.max_delta_e);
+
+    let reversed = compare_gradients(gradient, |t| blue.interpolate(&red, t), 3);
+    assert_eq!(reversed.max_delta_e, red.delta_e(&blue));
+}
+
+#[test]
+fn test_white_balance_removes_cast() {
+    let warm = RGB {
+        r: 0.5,
+        g: 0.4,
+        b: 0.3,
+    };
+    let scene = [warm, warm.tint(&RGB::BLACK, 0.5), RGB::BLACK];
+
+    for method in [WhiteBalanceMethod::GrayWorld, WhiteBalanceMethod::MaxRgb] {
+        let balance = estimate_white_balance(&scene, method);
+        let corrected = HCL::from(balance.apply(&warm));
+        assert!(corrected.is_achromatic(), "{method:?}: {corrected:?}");
+        assert!((corrected.l - HCL::from(warm).l).abs() < 0.01);
+    }
+
+    assert_eq!(
+        estimate_white_balance(&[], WhiteBalanceMethod::GrayWorld),
+        WhiteBalance::default()
+    );
+}
+
+#[test]
+fn test_skin_tone_protection() {
+    let region = ProtectedRegion::default();
+    let skin = CIELUV::from(RGB {
+        r: 0.87,
+        g: 0.67,
+        b: 0.55,
+    });
+    let sky = CIELUV::from(RGB {
+        r: 0.4,
+        g: 0.6,
+        b: 0.9,
+    });
+
+    assert!(region.weight(&skin) > 0.5);
+    assert_eq!(region.weight(&sky), 0.0);
+    assert_eq!(
+        sky.boost_saturation_protected(1.5, &region),
+        sky.boost_saturation(1.5)
+    );
+
+    let boosted = skin.boost_saturation_protected(1.5, &region).chroma();
+    assert!(boosted > skin.chroma());
+    assert!(boosted < skin.boost_saturation(1.5).chroma());
+    assert!(skin.vibrance_protected(1.0, &region).chroma() < skin.vibrance(1.0).chroma());
+}
+
+#[test]
+fn test_reference_vectors() {
+    fn close(a: [f32; 3], b: [f32; 3], what: &str) {
+        for i in 0..3 {
+            assert!(
+                (a[i] - b[i]).abs() < reference::TOLERANCE,
+                "{what}: found {a:?}, expected {b:?}"
+            );
+        }
+    }
+
+    for color in reference::SRGB {
+        let xyz = XYZ::from(color.rgb);
+        let cieluv = CIELUV::from(color.xyz);
+        close(
+            [xyz.x, xyz.y, xyz.z],
+            [color.xyz.x, color.xyz.y, color.xyz.z],
+            "xyz",
+        );
+        close(
+            [cieluv.l, cieluv.u, cieluv.v],
+            [color.cieluv.l, color.cieluv.u, color.cieluv.v],
+            "cieluv",
+        );
+    }
+}
+
+#[test]
+fn test_dynamic_pipeline() {
+    let color = CIELUV::from(RGB {
+        r: 0.2,
+        g: 0.6,
+        b: 0.4,
+    });
+
+    let config = [ColorOp::BoostSaturation(1.2), ColorOp::LimitLightness(50.0)];
+    let darken = |c: CIELUV| c.with_lightness(c.l * 0.5);
+    let ops: [&dyn DynColorOp; 3] = [&config[0], &config[1], &darken];
+    let pipeline = Pipeline::new(&ops);
+
+    let expected = darken(color.boost_saturation(1.2).limit_lightness(50.0));
+    assert_eq!(pipeline.apply(color), expected);
+    assert_eq!(Pipeline::new(&[]).apply(color), color);
+}
+
+#[test]
+fn test_gradient_steps() {
+    let start = CIELUV::from(RGB {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+    });
+    let end = CIELUV::from(RGB {
+        r: 0.0,
+        g: 0.0,
+        b: 1.0,
+    });
+
+    let steps = start.steps(&end, 100);
+    assert_eq!(steps.len(), 100);
+    for (i, color) in steps.enumerate() {
+        let expected = start.interpolate(&end, i as f32 / 99.0);
+        assert!(color.delta_e(&expected) < 0.0001, "step {i}");
+    }
+    assert_eq!(start.steps(&end, 100).last(), Some(end));
+    assert_eq!(start.steps(&end, 1).collect::<std::vec::Vec<_>>(), [start]);
+}
+
+#[test]
+fn test_color_buffer() {
+    let red = RGB {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+    };
+    let blue = RGB {
+        r: 0.0,
+        g: 0.0,
+        b: 1.0,
+    };
+    let start = ColorBuffer::from([red, blue]);
+    let end = ColorBuffer::from([blue, red]);
+
+    let middle = start.interpolate(&end, 0.5);
+    let expected = CIELUV::from(red).interpolate(&blue.into(), 0.5);
+    assert_eq!(middle.get(0), expected);
+    assert!(middle.get(1).delta_e(&expected) < 0.0001);
+    assert_eq!(<[CIELUV; 2]>::from(&start), [red.into(), blue.into()]);
+    approximately_equal(end.to_rgbw()[1], red.into());
+}
+
+#[test]
+fn test_self_check() {
+    assert!(self_check());
+}
+
+#[test]
+fn test_black_point() {
+    let dim = RGB8 { r: 0, g: 1, b: 255 };
+    assert_eq!(dim.black_point(3), RGB8 { r: 0, g: 3, b: 255 });
+    assert_eq!(
+        RGB8 { r: 128, g: 2, b: 0 }.black_point(0),
+        RGB8 { r: 128, g: 2, b: 0 }
+    );
+
+    let rgbw = RGBW {
+        r: 0.0,
+        g: 0.001,
+        b: 0.01,
+        w: 1.0,
+    };
+    let compensated = rgbw.black_point(0.01);
+    assert_eq!(compensated.r, 0.0);
+    assert_eq!(compensated.g, 0.0);
+    assert!(compensated.b > 0.01);
+    assert_eq!(compensated.w, 1.0);
+}
+
+#[test]
+fn test_interpolate_hsv() {
+    // Naive HSV interpolation from red to blue passes through green.
+    let middle = RGB::RED.interpolate_hsv(&RGB::BLUE, 0.5);
+    approximately_equal(middle.into(), RGB::GREEN.into());
+
+    let orange = RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0,
+    };
+    approximately_equal(
+        RGB::RED.interpolate_hsv(&RGB::RED, 0.3).into(),
+        RGB::RED.into(),
+    );
+    approximately_equal(
+        orange.interpolate_hsv(&RGB::BLACK, 0.0).into(),
+        orange.into(),
+    );
+    approximately_equal(
+        orange.interpolate_hsv(&RGB::BLACK, 1.0).into(),
+        RGB::BLACK.into(),
+    );
+}
+
+#[test]
+fn test_detect_banding() {
+    let black = CIELUV::from(RGB::BLACK);
+    let white = CIELUV::from(RGB::WHITE);
+    let dark = CIELUV::from(RGB {
+        r: 0.02,
+        g: 0.02,
+        b: 0.02,
+    });
+
+    // Too few LEDs for a full range gradient jumps between levels at every step.
+    let mut jumps = detect_banding(|t| black.interpolate(&white, t), 16, 8, 4);
+    assert!(matches!(jumps.next(), Some(Banding::Jump { levels, .. }) if levels > 1));
+    assert_eq!(jumps.count(), 16 - 2);
+
+    // A subtle gradient spreads a few levels over many LEDs.
+    let plateaus = detect_banding(|t| black.interpolate(&dark, t), 100, 8, 10);
+    assert!(plateaus.count() > 0);
+
+    // A constant gradient has no banding.
+    assert_eq!(detect_banding(|_| dark, 100, 8, 10).count(), 0);
+}
+
+#[test]
+fn test_color_enum() {
+    let colors = [
+        Color::from(RGB::RED),
+        Color::Luv(RGB::RED.into()),
+        Color::Hcl(RGB::RED.into()),
+        Color::Xyz(RGB::RED.into()),
+    ];
+    for color in colors {
+        approximately_equal(color.to::<RGB>().into(), RGB::RED.into());
+        approximately_equal(color.convert::<RGB>().into(), RGB::RED.into());
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_color_serde() {
+    let colors = [
+        Color::from(RGB::RED),
+        Color::from(HCL::from(RGB::BLUE)),
+        Color::from(XYZ::from(RGB::GREEN)),
+    ];
+    let json = serde_json::to_string(&colors).unwrap();
+    assert!(json.starts_with(r#"[{"Rgb":{"r":1.0,"g":0.0,"b":0.0}},{"Hcl":"#));
+    assert_eq!(serde_json::from_str::<[Color; 3]>(&json).unwrap(), colors);
+
+    let config: Color =
+        serde_json::from_str(r#"{"Xyz": {"x": 0.95047, "y": 1.0, "z": 1.08883}}"#).unwrap();
+    approximately_equal(config.to::<RGB>().into(), RGB::WHITE.into());
+}
+
+#[test]
+fn test_lstar() {
+    assert_eq!(round(linear_to_lstar(1.0)), 100.0);
+    assert_eq!(round(linear_to_lstar(0.18)), 49.5);
+    assert_eq!(linear_to_lstar(0.0), 0.0);
+    for l in [0.0, 5.0, 8.0, 8.1, 50.0, 100.0] {
+        assert!(
+            (linear_to_lstar(lstar_to_linear(l)) - l).abs() < 0.001,
+            "{l}"
+        );
+    }
+}
+
+#[test]
+fn test_channel_vec() {
+    let primaries = [RGB::RED, RGB::GREEN, RGB::BLUE].map(XYZ::from);
+    let (duty, error) = ChannelVec::solve(&primaries, &XYZ::from(RGB::WHITE));
+    assert!(error < 0.001);
+    assert_eq!(duty.0.map(round), [1.0; 3]);
+
+    let dim = ChannelVec([0.0, 0.5, 1.0]).scale(0.5);
+    assert_eq!(dim, ChannelVec([0.0, 0.25, 0.5]));
+    assert_eq!(dim.lerp(&duty, 0.5).0.map(round), [0.5, 0.63, 0.75]);
+    assert_eq!(
+        ChannelVec([-0.5, 0.5, 1.5]).clamp(0.0, 1.0),
+        ChannelVec([0.0, 0.5, 1.0])
+    );
+}
+
+#[test]
+fn test_hsluv() {
+    // Reference values from the HSLuv reference implementation.
+    let close = |hsluv: HSLuv, [h, s, l]: [f32; 3]| {
+        assert!((hsluv.h - h).abs() < 0.05, "{hsluv:?}");
+        assert!((hsluv.s - s).abs() < 0.05, "{hsluv:?}");
+        assert!((hsluv.l - l).abs() < 0.05, "{hsluv:?}");
+    };
+    let red = HSLuv::from(RGB::RED);
+    close(red, [12.18, 100.0, 53.24]);
+    close(HSLuv::from(RGB::BLUE), [265.87, 100.0, 32.30]);
+    let steel = RGB {
+        r: 0.2,
+        g: 0.4,
+        b: 0.6,
+    };
+    close(HSLuv::from(steel), [246.94, 78.45, 42.01]);
+    assert_eq!(round(HSLuv::from(RGB::WHITE).l), 100.0);
+    approximately_equal(RGB::from(red).into(), RGB::RED.into());
+    approximately_equal(RGB::from(HSLuv::from(steel)).into(), steel.into());
+
+    // Full saturation stays within the gamut at every hue.
+    for h in (0..360).step_by(15) {
+        let rgb = RGB::from(HSLuv {
+            h: h as f32,
+            s: 100.0,
+            l: 50.0,
+        });
+        for c in [rgb.r, rgb.g, rgb.b] {
+            assert!((-0.001..=1.001).contains(&c), "hue {h}: {rgb}");
+        }
+    }
+}
+
+#[test]
+fn test_downsample() {
+    let src = [RGB::BLACK, RGB::WHITE, RGB::RED, RGB::RED, RGB::BLUE];
+    let mut dst = [RGB::default(); 2];
+    downsample(&src, &mut dst);
+
+    // Half black and half white is 50% light, which is brighter than sRGB 0.5.
+    assert_eq!(round(dst[0].r), 0.74);
+    assert_eq!(dst[0].r, dst[0].g);
+    // Two thirds red and one third blue.
+    approximately_equal(
+        dst[1].into(),
+        RGB {
+            r: 0.84,
+            g: 0.0,
+            b: 0.61,
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_hpluv() {
+    // Reference values from the HSLuv reference implementation.
+    let close = |hpluv: HPLuv, [h, p, l]: [f32; 3]| {
+        assert!((hpluv.h - h).abs() < 0.05, "{hpluv:?}");
+        assert!((hpluv.p - p).abs() < 0.1, "{hpluv:?}");
+        assert!((hpluv.l - l).abs() < 0.05, "{hpluv:?}");
+    };
+    close(HPLuv::from(RGB::RED), [12.18, 426.75, 53.24]);
+    close(
+        HPLuv::from(RGB {
+            r: 0.2,
+            g: 0.4,
+            b: 0.6,
+        }),
+        [246.94, 156.13, 42.01],
+    );
+
+    // Constant saturation and lightness keeps the same chroma at every hue, without clipping.
+    let chroma = HCL::from(HPLuv {
+        h: 0.0,
+        p: 100.0,
+        l: 60.0,
+    })
+    .c;
+    for h in (0..360).step_by(15) {
+        let hpluv = HPLuv {
+            h: h as f32,
+            p: 100.0,
+            l: 60.0,
+        };
+        let rgb = RGB::from(hpluv);
+        for c in [rgb.r, rgb.g, rgb.b] {
+            assert!((-0.001..=1.001).contains(&c), "hue {h}: {rgb}");
+        }
+        assert_eq!(HCL::from(hpluv).c, chroma);
+        assert!(HSLuv::from(CIELUV::from(hpluv)).s <= 100.01);
+    }
+}
+
+#[test]
+fn test_dithered() {
+    let exact = RGB::from(RGB8 {
+        r: 0,
+        g: 128,
+        b: 255,
+    });
+    for i in 0..100 {
+        assert_eq!(
+            RGB8::dithered(&exact, i),
+            RGB8 {
+                r: 0,
+                g: 128,
+                b: 255
+            }
+        );
+    }
+
+    // Between two levels, the LEDs alternate so that they average out to the color.
+    let between = RGB {
+        r: 100.25 / 255.0,
+        g: 0.0,
+        b: 0.0,
+    };
+    let sum: u32 = (0..1000)
+        .map(|i| RGB8::dithered(&between, i).r as u32)
+        .sum();
+    assert_eq!((sum as f32 / 1000.0 * 100.0).round() / 100.0, 100.25);
+}
+
+#[test]
+fn test_oklab() {
+    let white = Oklab::from(RGB::WHITE);
+    assert_eq!([white.l, white.a, white.b].map(round), [1.0, 0.0, 0.0]);
+
+    let red = Oklab::from(RGB::RED);
+    assert_eq!([red.l, red.a, red.b].map(round), [0.63, 0.22, 0.13]);
+    approximately_equal(RGB::from(red).into(), RGB::RED.into());
+
+    let middle = red.interpolate(&Oklab::from(RGB::BLUE), 0.5);
+    assert!(middle.l > 0.5 && middle.l < 0.6, "{middle}");
+}
+
+#[test]
+fn test_oklch() {
+    assert!(Oklch::from(RGB::WHITE).is_achromatic());
+    assert_eq!(Oklch::from(RGB::BLACK).hue(), None);
+
+    let red = Oklch::from(RGB::RED);
+    assert_eq!([red.l, red.c, red.h].map(round), [0.63, 0.26, 29.23]);
+    approximately_equal(RGB::from(red).into(), RGB::RED.into());
+    approximately_equal(RGBW::from(red), RGBW::from(CIELUV::from(RGB::RED)));
+
+    let rotated = red.rotate_hue(-60.0);
+    assert_eq!(round(rotated.h), 329.23);
+    assert_eq!(rotated.c, red.c);
+}
+
+#[test]
+fn test_cielab() {
+    // Reference values from Bruce Lindbloom's color calculator.
+    let red = CIELAB::from(RGB::RED);
+    assert_eq!([red.l, red.a, red.b].map(round), [53.24, 80.09, 67.2]);
+    let blue = CIELAB::from(RGB::BLUE);
+    assert_eq!([blue.l, blue.a, blue.b].map(round), [32.3, 79.19, -107.86]);
+    assert_eq!(round(CIELAB::from(RGB::WHITE).l), 100.0);
+    for rgb in [RGB::RED, RGB::BLUE, RGB::SEPIA, RGB::WHITE] {
+        approximately_equal(RGB::from(CIELAB::from(rgb)).into(), rgb.into());
+    }
+
+    let lch = LChab::from(red);
+    assert_eq!([lch.l, lch.c, lch.h].map(round), [53.24, 104.55, 40.0]);
+    assert_eq!(round(red.chroma()), 104.55);
+    assert_eq!(round(red.hue()), 40.0);
+    approximately_equal(RGB::from(lch).into(), RGB::RED.into());
+    assert_eq!(LChab::from(RGB::WHITE).hue(), None);
+    assert_eq!(Color::from(lch).to::<LChab>(), lch);
+}
+
+#[test]
+fn test_rgbw_from_pastel() {
+    let red = CIELUV::from(RGB::RED);
+    approximately_equal(RGBW::from_pastel(&red, 2.0), RGBW::from(red));
+
+    let pink = CIELUV::from(RGB {
+        r: 1.0,
+        g: 0.8,
+        b: 0.85,
+    });
+    let washed = RGBW::from(pink);
+    let pastel = RGBW::from_pastel(&pink, 2.0);
+    assert_eq!(pastel.w, washed.w);
+    assert!(
+        pastel.r - pastel.g > washed.r - washed.g,
+        "{pastel} {washed}"
+    );
+}
+
+#[test]
+fn test_negative_values() {
+    // Slightly outside of the gamut beyond green, with a tiny negative blue component.
+    let green = XYZ::from(RGB::GREEN);
+    let xyz = XYZ {
+        z: green.z - 0.0005,
+        ..green
+    };
+    let propagated = RGB::from_xyz(&xyz, NegativeValues::Propagate);
+    assert!(propagated.b < 0.0, "{propagated}");
+
+    assert_eq!(RGB::from_xyz(&xyz, NegativeValues::Clamp), RGB::from(xyz));
+    assert_eq!(RGB::from_xyz(&xyz, NegativeValues::Clamp).b, 0.0);
+    let flushed = RGB::from_xyz(&xyz, NegativeValues::Flush { epsilon: 0.001 });
+    assert_eq!(flushed.b, 0.0);
+    let outside = RGB::from_xyz(&xyz, NegativeValues::Flush { epsilon: 0.0001 });
+    assert_eq!(outside.b, propagated.b);
+}
+
+#[test]
+fn test_hsl() {
+    let orange = RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0,
+    };
+    let hsl = HSL::from(orange);
+    assert_eq!([hsl.h, hsl.s, hsl.l].map(round), [30.0, 1.0, 0.5]);
+    approximately_equal(RGB::from(hsl).into(), orange.into());
+
+    let pink = HSL {
+        h: 330.0,
+        s: 0.6,
+        l: 0.8,
+    };
+    let rgb = RGB::from(pink);
+    assert_eq!([rgb.r, rgb.g, rgb.b].map(round), [0.92, 0.68, 0.8]);
+    assert_eq!(HSL::from(RGB::WHITE).s, 0.0);
+}
+
+#[test]
+fn test_hsv() {
+    let orange = RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0,
+    };
+    let hsv = HSV::from(orange);
+    assert_eq!([hsv.h, hsv.s, hsv.v].map(round), [30.0, 1.0, 1.0]);
+    approximately_equal(RGB::from(hsv).into(), orange.into());
+
+    let cieluv = CIELUV::from(hsv);
+    assert_eq!(cieluv, CIELUV::from(orange));
+    approximately_equal(RGB::from(HSV::from(cieluv)).into(), orange.into());
+}
+
+#[test]
+fn test_color_filter() {
+    let black = CIELUV::from(RGB::BLACK);
+    let white = CIELUV::from(RGB::WHITE);
+    let mut filter = ColorFilter::new(Duration::from_millis(100));
+
+    assert_eq!(filter.update(black, Duration::from_millis(10)), black);
+    let smoothed = filter.update(white, Duration::from_millis(100));
+    assert_eq!(round(smoothed.l / white.l), 0.63);
+
+    // Ten short updates make up one long one.
+    filter.reset();
+    filter.update(black, Duration::ZERO);
+    for _ in 0..10 {
+        filter.update(white, Duration::from_millis(10));
+    }
+    assert_eq!(round(filter.value().unwrap().l), round(smoothed.l));
+}
+
+#[test]
+fn test_hwb() {
+    let pastel = RGB {
+        r: 1.0,
+        g: 0.6,
+        b: 0.2,
+    };
+    let hwb = HWB::from(pastel);
+    assert_eq!([hwb.h, hwb.w, hwb.b].map(round), [30.0, 0.2, 0.0]);
+    approximately_equal(RGB::from(hwb).into(), pastel.into());
+
+    let gray = HWB {
+        h: 120.0,
+        w: 0.6,
+        b: 0.6,
+    };
+    approximately_equal(
+        RGB::from(gray).into(),
+        RGB {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+        }
+        .into(),
+    );
+    assert_eq!(HSV::from(HWB::from(HSV::from(pastel))), HSV::from(pastel));
+}
+
+#[test]
+fn test_slew() {
+    let black = CIELUV::from(RGB::BLACK);
+    let white = CIELUV::from(RGB::WHITE);
+
+    let step = slew(&black, &white, 1.0);
+    assert_eq!(round(step.delta_e(&black)), 1.0);
+    assert_eq!(slew(&step, &white, 100.0), white);
+
+    let mut color = black;
+    let mut frames = 0;
+    while color != white {
+        color = slew(&color, &white, 1.0);
+        frames += 1;
+    }
+    assert_eq!(frames, 9);
+}
+
+#[test]
+fn test_cmyk() {
+    let orange = RGB {
+        r: 0.8,
+        g: 0.4,
+        b: 0.0,
+    };
+    let cmyk = CMYK::from(orange);
+    assert_eq!(
+        [cmyk.c, cmyk.m, cmyk.y, cmyk.k].map(round),
+        [0.0, 0.5, 1.0, 0.2]
+    );
+    approximately_equal(RGB::from(cmyk).into(), orange.into());
+    assert_eq!(CMYK::from(RGB::BLACK).k, 1.0);
+    approximately_equal(Color::from(cmyk).to::<RGB>().into(), orange.into());
+}
+
+#[test]
+fn test_dirty_buffer() {
+    let mut buffer = DirtyBuffer::<4>::default();
+    let mut out = [RGBW::default(); 4];
+    assert_eq!(buffer.convert_dirty(&mut out), 4);
+    assert_eq!(buffer.convert_dirty(&mut out), 0);
+
+    let red = CIELUV::from(RGB::RED);
+    buffer.set(2, red);
+    buffer.set(3, CIELUV::default());
+    assert_eq!(buffer.dirty_count(), 1);
+    assert_eq!(buffer.convert_dirty(&mut out), 1);
+    assert_eq!(out[2], RGBW::from(red));
+}
+
+#[test]
+fn test_ycbcr() {
+    let white = YCbCr::from_rgb(&RGB::WHITE, YCbCrMatrix::Bt709, YCbCrRange::Limited);
+    assert_eq!(
+        [white.y, white.cb, white.cr].map(|c| (c * 255.0).round()),
+        [235.0, 128.0, 128.0]
+    );
+
+    let red = YCbCr::from_rgb(&RGB::RED, YCbCrMatrix::Bt601, YCbCrRange::Full);
+    assert_eq!(
+        [red.y, red.cb, red.cr].map(|c| (c * 255.0).round()),
+        [76.0, 85.0, 255.0]
+    );
+
+    let orange = RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.1,
+    };
+    for matrix in [YCbCrMatrix::Bt601, YCbCrMatrix::Bt709] {
+        for range in [YCbCrRange::Full, YCbCrRange::Limited] {
+            let ycbcr = YCbCr::from_rgb(&orange, matrix, range);
+            approximately_equal(ycbcr.to_rgb(matrix, range).into(), orange.into());
+        }
+    }
+}
+
+#[test]
+fn test_matrix_layout() {
+    assert_eq!(MatrixLayout::RowMajor.index(1, 2, 4, 3), 9);
+    assert_eq!(MatrixLayout::ColumnMajor.index(1, 2, 4, 3), 5);
+    assert_eq!(MatrixLayout::Serpentine.index(0, 1, 4, 3), 7);
+    assert_eq!(MatrixLayout::ColumnSerpentine.index(1, 0, 4, 3), 5);
+
+    // Two 4x4 serpentine panels side by side.
+    let tiled = TiledLayout {
+        panel_width: 4,
+        panel_height: 4,
+        panel_layout: MatrixLayout::Serpentine,
+        panels_x: 2,
+        panels_y: 1,
+        tile_layout: MatrixLayout::RowMajor,
+    };
+    assert_eq!(tiled.width(), 8);
+    assert_eq!(tiled.index(4, 0), 16);
+    assert_eq!(tiled.index(4, 1), 23);
+
+    // Every LED maps to a unique index.
+    let mut seen = [false; 32];
+    for y in 0..tiled.height() {
+        for x in 0..tiled.width() {
+            assert!(!std::mem::replace(&mut seen[tiled.index(x, y)], true));
+        }
+    }
+}
+
+#[test]
+fn test_xyy() {
+    let (x, y) = RGB::WHITE.chromaticity();
+    assert_eq!(
+        [x, y].map(|c| (c * 10000.0).round() / 10000.0),
+        [0.3127, 0.329]
+    );
+    assert!((xyY::from(XYZ::from(RGB::BLACK)).x - x).abs() < 1e-6);
+
+    let (x, y) = CIELUV::from(RGB::RED).chromaticity();
+    assert_eq!([x, y].map(round), [0.64, 0.33]);
+
+    let green = XYZ::from(RGB::GREEN);
+    let xyy = xyY::from(green);
+    assert_eq!(xyy.luminance, green.y);
+    let back = XYZ::from(xyy);
+    assert!((back.x - green.x).abs() < 1e-6 && (back.z - green.z).abs() < 1e-6);
+}
+
+#[test]
+fn test_cam16_ucs() {
+    // White keeps a slight tint, as the eye adapts to it incompletely.
+    let white = CAM16UCS::from(RGB::WHITE);
+    assert_eq!(round(white.j), 100.0);
+    assert!(white.a.hypot(white.b) < 3.0);
+
+    let night = ViewingConditions::new(1.0, 20.0, Surround::Dark);
+    for rgb in [RGB::RED, RGB::GREEN, RGB::BLUE, RGB::SEPIA] {
+        approximately_equal(RGB::from(CAM16UCS::from(rgb)).into(), rgb.into());
+        let cam16 = CAM16UCS::from_xyz(&rgb.into(), &night);
+        approximately_equal(RGB::from(cam16.to_xyz(&night)).into(), rgb.into());
+    }
+
+    // A dark surround lowers the perceived contrast, so dark colors appear lighter relative to white.
+    let sepia = CAM16UCS::from(RGB::SEPIA);
+    assert!(CAM16UCS::from_xyz(&RGB::SEPIA.into(), &night).j > sepia.j);
+    assert_eq!(
+        round(sepia.delta_e(&sepia.interpolate(&white, 0.5)) * 2.0),
+        round(sepia.delta_e(&white))
+    );
+}
+
+#[test]
+fn test_linear_rgb() {
+    let white = LinearRGB::from(RGB::WHITE);
+    assert_eq!(round(white.luminance()), 1.0);
+
+    // Half the light of white is considerably brighter than sRGB 0.5.
+    let half = RGB::from(white.scale(0.5));
+    assert_eq!(round(half.r), 0.74);
+    assert_eq!(
+        RGB::from(LinearRGB::from(RGB::RED).mix(&LinearRGB::from(RGB::BLACK), 0.5)),
+        RGB::RED.tint(&RGB::BLACK, 0.5)
+    );
+
+    let orange = RGB {
+        r: 1.0,
+        g: 0.5,
+        b: 0.1,
+    };
+    approximately_equal(
+        RGB::from(LinearRGB::from(XYZ::from(orange))).into(),
+        orange.into(),
+    );
+    assert_eq!(XYZ::from(LinearRGB::from(orange)), XYZ::from(orange));
+}
+
+#[test]
+fn test_p3() {
+    // sRGB red is well inside the P3 gamut.
+    let red = P3::from(RGB::RED);
+    assert_eq!(
+        (round(red.r), round(red.g), round(red.b)),
+        (0.92, 0.2, 0.14)
+    );
+    let white = P3::from(RGB::WHITE);
+    assert_eq!(
+        (round(white.r), round(white.g), round(white.b)),
+        (1.0, 1.0, 1.0)
+    );
+    approximately_equal(RGB::from(red).into(), RGB::RED.into());
+
+    // P3 red is outside of the sRGB gamut.
+    let saturated = P3 {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+    };
+    let xyz = XYZ::from(saturated);
+    assert!(xyz.linear_rgb().1 < 0.0);
+    assert_eq!(RGB::from(saturated).g, 0.0);
+    approximately_equal(RGB::from(P3::from(xyz)).into(), RGB::from(saturated).into());
+}
+
+#[test]
+fn test_adobe_rgb() {
+    // Adobe RGB and sRGB share the red and blue primaries.
+    let red = AdobeRGB::from(RGB::RED);
+    assert_eq!((round(red.r), round(red.g), round(red.b)), (0.86, 0.0, 0.0));
+    let white = AdobeRGB::from(RGB::WHITE);
+    assert_eq!(
+        (round(white.r), round(white.g), round(white.b)),
+        (1.0, 1.0, 1.0)
+    );
+
+    // Shadows are encoded differently because of the transfer function.
+    let gray = RGB::from(AdobeRGB {
+        r: 0.2,
+        g: 0.2,
+        b: 0.2,
+    });
+    assert_eq!(round(gray.g), 0.19);
+
+    // Adobe RGB green is outside of the sRGB gamut.
+    let green = AdobeRGB {
+        r: 0.0,
+        g: 1.0,
+        b: 0.0,
+    };
+    assert!(XYZ::from(green).linear_rgb().0 < 0.0);
+    approximately_equal(
+        RGB::from(AdobeRGB::from(RGB::GREEN)).into(),
+        RGB::GREEN.into(),
+    );
+}
+
+#[test]
+fn test_apply_masked() {
+    let start = CIELUV::from(RGB::RED);
+    let end = CIELUV::from(RGB::BLUE);
+    let mut buffer = [RGB::BLACK; 5];
+    apply_masked(
+        |t| start.interpolate(&end, t),
+        &[0.0, 1.0, 1.0, 0.5, 2.0],
+        &mut buffer,
+    );
+
+    assert_eq!(buffer[0], RGB::BLACK);
+    approximately_equal(
+        buffer[1].into(),
+        RGB::from(start.interpolate(&end, 0.25)).into(),
+    );
+    approximately_equal(buffer[4].into(), RGB::BLUE.into());
+
+    // Half alpha over black is half the light, brighter than half the sRGB value.
+    let full = RGB::from(start.interpolate(&end, 0.75));
+    assert!(buffer[3].b > full.b / 2.0);
+    approximately_equal(buffer[3].into(), RGB::BLACK.tint(&full, 0.5).into());
+}
+
+#[test]
+fn test_stretch_palette_lightness() {
+    let lstar = |color: CIELUV| linear_to_lstar(XYZ::from(color).y);
+    let mut palette = [RGB::BLACK, RGB::RED, RGB::WHITE].map(CIELUV::from);
+    let red = HCL::from(palette[1]);
+
+    stretch_palette_lightness(&mut palette, 10.0, 50.0);
+    assert_eq!(round(lstar(palette[0])), 10.0);
+    assert_eq!(round(lstar(palette[2])), 50.0);
+    assert_eq!(round(palette[2].chroma()), 0.0);
+
+    // Red at L* 53.24 keeps its perceptual position between black and white.
+    assert_eq!(round(lstar(palette[1])), 31.3);
+    // Red keeps its hue and stays at the same relative chroma, fully saturated.
+    let dimmed = HCL::from(palette[1]);
+    assert!(dimmed.l < red.l);
+    assert_eq!(round(dimmed.h), round(red.h));
+    assert_eq!(round(dimmed.c), round(max_chroma(dimmed.l, dimmed.h)));
+
+    let mut flat = [CIELUV::from(RGB::RED); 2];
+    stretch_palette_lightness(&mut flat, 20.0, 40.0);
+    assert_eq!(round(lstar(flat[0])), 30.0);
+}